@@ -0,0 +1,410 @@
+//! # Cost basis
+//!
+//! Matches sells against the lots acquired by buys, computing the realized gains
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use chrono::{DateTime, FixedOffset};
+use rust_decimal::Decimal;
+
+use crate::model::{Trade, TradeStatus, TradeType};
+
+/// Describes which lots are consumed by a sell
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum CostBasisMethod {
+    /// First in, first out: the oldest lots are sold first
+    Fifo,
+    /// Last in, first out: the most recent lots are sold first
+    Lifo,
+    /// Weighted average: all the lots of an asset share the same unit cost.
+    /// Lots are still consumed in FIFO order to track the holding period
+    Average,
+}
+
+/// An amount of an asset acquired by a buy
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Lot {
+    /// Id of the buy trade
+    pub trade_id: String,
+    pub symbol: String,
+    pub acquired: DateTime<FixedOffset>,
+    /// Amount of the asset still held
+    pub amount: Decimal,
+    /// Cost in EUR of the amount still held
+    pub cost_eur: Decimal,
+}
+
+impl Lot {
+    /// Cost in EUR of a unit of the asset
+    pub fn unit_cost_eur(&self) -> Decimal {
+        if self.amount.is_zero() {
+            Decimal::ZERO
+        } else {
+            self.cost_eur / self.amount
+        }
+    }
+
+    /// Take `amount` out of the lot, returning the match
+    fn take(&mut self, amount: Decimal) -> LotMatch {
+        let amount = amount.min(self.amount);
+        let cost_eur = if amount == self.amount {
+            self.cost_eur
+        } else {
+            self.cost_eur * amount / self.amount
+        };
+        self.amount -= amount;
+        self.cost_eur -= cost_eur;
+
+        LotMatch {
+            trade_id: self.trade_id.clone(),
+            acquired: self.acquired,
+            amount,
+            cost_eur,
+        }
+    }
+}
+
+/// The part of a lot consumed by a sell
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LotMatch {
+    /// Id of the buy trade of the lot
+    pub trade_id: String,
+    pub acquired: DateTime<FixedOffset>,
+    pub amount: Decimal,
+    pub cost_eur: Decimal,
+}
+
+/// A sell, with the lots it consumed
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Disposal {
+    /// Id of the sell trade
+    pub trade_id: String,
+    pub symbol: String,
+    pub datetime: DateTime<FixedOffset>,
+    /// Amount of the asset sold
+    pub amount: Decimal,
+    /// Proceeds of the sell in EUR
+    pub proceeds_eur: Decimal,
+    /// Cost in EUR of the lots consumed by the sell
+    pub cost_eur: Decimal,
+    /// Amount sold which is not covered by any lot (e.g. the asset was deposited from another exchange).
+    /// This amount has a zero cost basis
+    pub uncovered_amount: Decimal,
+    /// Lots consumed by the sell
+    pub lots: Vec<LotMatch>,
+}
+
+impl Disposal {
+    /// Realized gain in EUR; negative if the sell realized a loss
+    pub fn gain_eur(&self) -> Decimal {
+        self.proceeds_eur - self.cost_eur
+    }
+}
+
+/// Lots and realized gains computed from a trade history
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CostBasis {
+    pub method: CostBasisMethod,
+    /// Lots still held, ordered by acquisition time
+    pub open_lots: Vec<Lot>,
+    /// Sells, ordered by time
+    pub disposals: Vec<Disposal>,
+}
+
+impl CostBasis {
+    /// Compute the cost basis of `trades` with the provided method.
+    ///
+    /// Trades can be provided in any order; only finished trades are taken into account.
+    /// The legs of swaps found in `related_swap_trade` are included, unless already present in `trades`.
+    pub fn compute(trades: &[Trade], method: CostBasisMethod) -> Self {
+        let mut lots: HashMap<String, VecDeque<Lot>> = HashMap::new();
+        let mut disposals = Vec::new();
+
+        for trade in Self::sorted_trades(trades) {
+            let symbol_lots = lots.entry(trade.symbol.clone()).or_default();
+            let value_eur = trade.amount_fiat * trade.fiat_to_eur_rate;
+            match trade.r#type {
+                TradeType::Buy => {
+                    symbol_lots.push_back(Lot {
+                        trade_id: trade.id.clone(),
+                        symbol: trade.symbol.clone(),
+                        acquired: trade.datetime,
+                        amount: trade.amount_asset,
+                        cost_eur: value_eur,
+                    });
+                    if method == CostBasisMethod::Average {
+                        Self::average_lots(symbol_lots);
+                    }
+                }
+                TradeType::Sell => {
+                    let mut remaining = trade.amount_asset;
+                    let mut matches = Vec::new();
+                    while remaining > Decimal::ZERO {
+                        let lot = match method {
+                            CostBasisMethod::Fifo | CostBasisMethod::Average => {
+                                symbol_lots.front_mut()
+                            }
+                            CostBasisMethod::Lifo => symbol_lots.back_mut(),
+                        };
+                        let Some(lot) = lot else {
+                            break;
+                        };
+                        let lot_match = lot.take(remaining);
+                        remaining -= lot_match.amount;
+                        matches.push(lot_match);
+                        symbol_lots.retain(|lot| !lot.amount.is_zero());
+                    }
+
+                    disposals.push(Disposal {
+                        trade_id: trade.id.clone(),
+                        symbol: trade.symbol.clone(),
+                        datetime: trade.datetime,
+                        amount: trade.amount_asset,
+                        proceeds_eur: value_eur,
+                        cost_eur: matches.iter().map(|lot| lot.cost_eur).sum(),
+                        uncovered_amount: remaining,
+                        lots: matches,
+                    });
+                }
+            }
+        }
+
+        let mut open_lots: Vec<Lot> = lots.into_values().flatten().collect();
+        open_lots.sort_by_key(|lot| lot.acquired);
+
+        Self {
+            method,
+            open_lots,
+            disposals,
+        }
+    }
+
+    /// Iterate over the open lots of `symbol`
+    pub fn open_lots_for<'a>(&'a self, symbol: &'a str) -> impl Iterator<Item = &'a Lot> {
+        self.open_lots
+            .iter()
+            .filter(move |lot| lot.symbol == symbol)
+    }
+
+    /// Amount of `symbol` held according to the open lots
+    pub fn holding(&self, symbol: &str) -> Decimal {
+        self.open_lots_for(symbol).map(|lot| lot.amount).sum()
+    }
+
+    /// Cost in EUR of the open lots of `symbol`
+    pub fn invested_eur(&self, symbol: &str) -> Decimal {
+        self.open_lots_for(symbol).map(|lot| lot.cost_eur).sum()
+    }
+
+    /// Realized gain in EUR of all the sells of `symbol`
+    pub fn realized_gain_eur(&self, symbol: &str) -> Decimal {
+        self.disposals
+            .iter()
+            .filter(|disposal| disposal.symbol == symbol)
+            .map(Disposal::gain_eur)
+            .sum()
+    }
+
+    /// Get the finished trades, including swap legs, sorted from the oldest
+    fn sorted_trades(trades: &[Trade]) -> Vec<&Trade> {
+        let mut seen = HashSet::new();
+        let mut sorted = Vec::with_capacity(trades.len());
+        let legs = trades.iter().chain(
+            trades
+                .iter()
+                .filter_map(|trade| trade.related_swap_trade.as_deref()),
+        );
+        for trade in legs {
+            if trade.status == TradeStatus::Finished && seen.insert(trade.id.as_str()) {
+                sorted.push(trade);
+            }
+        }
+        sorted.sort_by_key(|trade| trade.datetime);
+
+        sorted
+    }
+
+    /// Spread the total cost of the lots evenly on the amount held
+    fn average_lots(lots: &mut VecDeque<Lot>) {
+        let total_amount: Decimal = lots.iter().map(|lot| lot.amount).sum();
+        let mut remaining_cost: Decimal = lots.iter().map(|lot| lot.cost_eur).sum();
+        if total_amount.is_zero() {
+            return;
+        }
+        let total_cost = remaining_cost;
+        let last = lots.len() - 1;
+        for (i, lot) in lots.iter_mut().enumerate() {
+            lot.cost_eur = if i == last {
+                remaining_cost
+            } else {
+                total_cost * lot.amount / total_amount
+            };
+            remaining_cost -= lot.cost_eur;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use rust_decimal_macros::dec;
+
+    use crate::testing::fixture::{datetime, TradeBuilder};
+
+    #[test]
+    fn should_compute_fifo() {
+        let cost_basis = CostBasis::compute(&history(), CostBasisMethod::Fifo);
+
+        assert_eq!(cost_basis.disposals.len(), 1);
+        let disposal = &cost_basis.disposals[0];
+        assert_eq!(disposal.trade_id, "3");
+        assert_eq!(disposal.proceeds_eur, dec!(450));
+        assert_eq!(disposal.cost_eur, dec!(250));
+        assert_eq!(disposal.gain_eur(), dec!(200));
+        assert_eq!(disposal.uncovered_amount, Decimal::ZERO);
+        assert_eq!(
+            disposal
+                .lots
+                .iter()
+                .map(|lot| (lot.trade_id.as_str(), lot.amount))
+                .collect::<Vec<_>>(),
+            vec![("1", dec!(1)), ("2", dec!(0.5))]
+        );
+        assert_eq!(cost_basis.holding("BTC"), dec!(1.5));
+        assert_eq!(cost_basis.invested_eur("BTC"), dec!(300));
+        assert_eq!(cost_basis.realized_gain_eur("BTC"), dec!(200));
+    }
+
+    #[test]
+    fn should_compute_lifo() {
+        let cost_basis = CostBasis::compute(&history(), CostBasisMethod::Lifo);
+
+        let disposal = &cost_basis.disposals[0];
+        assert_eq!(disposal.cost_eur, dec!(300));
+        assert_eq!(disposal.gain_eur(), dec!(150));
+        assert_eq!(
+            disposal
+                .lots
+                .iter()
+                .map(|lot| (lot.trade_id.as_str(), lot.amount))
+                .collect::<Vec<_>>(),
+            vec![("2", dec!(1.5))]
+        );
+        assert_eq!(cost_basis.holding("BTC"), dec!(1.5));
+        assert_eq!(cost_basis.invested_eur("BTC"), dec!(250));
+    }
+
+    #[test]
+    fn should_compute_average() {
+        let cost_basis = CostBasis::compute(&history(), CostBasisMethod::Average);
+
+        // 3 BTC bought for 550 EUR
+        let disposal = &cost_basis.disposals[0];
+        assert_eq!(disposal.cost_eur, dec!(275));
+        assert_eq!(disposal.gain_eur(), dec!(175));
+        assert_eq!(cost_basis.holding("BTC"), dec!(1.5));
+        assert_eq!(cost_basis.invested_eur("BTC"), dec!(275));
+    }
+
+    #[test]
+    fn should_report_uncovered_amount() {
+        let cost_basis = CostBasis::compute(
+            &[
+                TradeBuilder::new("1")
+                    .r#type(TradeType::Buy)
+                    .datetime(datetime("2023-01-01T12:00:00+00:00"))
+                    .amounts(dec!(1), dec!(100))
+                    .build(),
+                TradeBuilder::new("2")
+                    .r#type(TradeType::Sell)
+                    .datetime(datetime("2023-02-01T12:00:00+00:00"))
+                    .amounts(dec!(3), dec!(600))
+                    .build(),
+            ],
+            CostBasisMethod::Fifo,
+        );
+
+        let disposal = &cost_basis.disposals[0];
+        assert_eq!(disposal.cost_eur, dec!(100));
+        assert_eq!(disposal.uncovered_amount, dec!(2));
+        assert!(cost_basis.open_lots.is_empty());
+    }
+
+    #[test]
+    fn should_include_swap_legs_and_skip_unfinished_trades() {
+        let swap_buy = TradeBuilder::new("3")
+            .r#type(TradeType::Buy)
+            .datetime(datetime("2023-02-01T12:00:00+00:00"))
+            .asset("5", "ETH")
+            .amounts(dec!(2), dec!(300))
+            .build();
+        let swap_sell = TradeBuilder::new("2")
+            .r#type(TradeType::Sell)
+            .datetime(datetime("2023-02-01T12:00:00+00:00"))
+            .amounts(dec!(1), dec!(300))
+            .swap(swap_buy)
+            .build();
+        let canceled = TradeBuilder::new("4")
+            .r#type(TradeType::Sell)
+            .status(TradeStatus::Canceled)
+            .datetime(datetime("2023-03-01T12:00:00+00:00"))
+            .amounts(dec!(1), dec!(300))
+            .build();
+
+        let cost_basis = CostBasis::compute(
+            &[
+                canceled,
+                swap_sell,
+                TradeBuilder::new("1")
+                    .r#type(TradeType::Buy)
+                    .datetime(datetime("2023-01-01T12:00:00+00:00"))
+                    .amounts(dec!(2), dec!(200))
+                    .build(),
+            ],
+            CostBasisMethod::Fifo,
+        );
+
+        assert_eq!(cost_basis.disposals.len(), 1);
+        assert_eq!(cost_basis.realized_gain_eur("BTC"), dec!(200));
+        assert_eq!(cost_basis.holding("BTC"), dec!(1));
+        assert_eq!(cost_basis.holding("ETH"), dec!(2));
+        assert_eq!(cost_basis.invested_eur("ETH"), dec!(300));
+    }
+
+    #[test]
+    fn should_convert_to_eur() {
+        let cost_basis = CostBasis::compute(
+            &[TradeBuilder::new("1")
+                .datetime(datetime("2023-01-01T12:00:00+00:00"))
+                .amounts(dec!(1), dec!(100))
+                .fiat("1", dec!(0.9))
+                .build()],
+            CostBasisMethod::Fifo,
+        );
+
+        assert_eq!(cost_basis.invested_eur("BTC"), dec!(90));
+    }
+
+    /// Buy 1 BTC for 150 EUR, buy 2 BTC for 400 EUR, sell 1.5 BTC for 450 EUR
+    fn history() -> Vec<Trade> {
+        vec![
+            TradeBuilder::new("3")
+                .r#type(TradeType::Sell)
+                .datetime(datetime("2023-03-01T12:00:00+00:00"))
+                .amounts(dec!(1.5), dec!(450))
+                .build(),
+            TradeBuilder::new("2")
+                .r#type(TradeType::Buy)
+                .datetime(datetime("2023-02-01T12:00:00+00:00"))
+                .amounts(dec!(2), dec!(400))
+                .build(),
+            TradeBuilder::new("1")
+                .r#type(TradeType::Buy)
+                .datetime(datetime("2023-01-01T12:00:00+00:00"))
+                .amounts(dec!(1), dec!(150))
+                .build(),
+        ]
+    }
+}
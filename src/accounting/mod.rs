@@ -0,0 +1,12 @@
+//! # Accounting
+//!
+//! Accounting on top of the user's trade history.
+//! All the amounts are computed in EUR, converting the fiat amounts with the trade `fiat_to_eur_rate`
+
+pub mod cost_basis;
+pub mod pnl;
+pub mod tax;
+
+pub use cost_basis::{CostBasis, CostBasisMethod, Disposal, Lot, LotMatch};
+pub use pnl::{AssetPnl, PnlReport};
+pub use tax::{FiscalYear, Jurisdiction, TaxLine, TaxLineKind, TaxReport};
@@ -0,0 +1,210 @@
+//! # Profit and loss
+//!
+//! Realized and unrealized gains per asset
+
+use std::collections::{BTreeSet, HashMap};
+
+use rust_decimal::Decimal;
+
+use super::{CostBasis, CostBasisMethod};
+use crate::model::{AssetWallet, Trade};
+
+/// Profit and loss of an asset, in EUR
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AssetPnl {
+    pub symbol: String,
+    /// Cost of the lots still held
+    pub invested_eur: Decimal,
+    /// Amount held according to the asset wallets
+    pub holding: Decimal,
+    /// Current price; `None` if the price is unknown
+    pub price_eur: Option<Decimal>,
+    /// Current value of the holding; `None` if the price is unknown
+    pub current_value_eur: Option<Decimal>,
+    /// Gain realized by the sells
+    pub realized_gain_eur: Decimal,
+    /// Current value minus the invested amount; `None` if the price is unknown
+    pub unrealized_gain_eur: Option<Decimal>,
+}
+
+/// Profit and loss report of the user's assets
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PnlReport {
+    pub method: CostBasisMethod,
+    /// Profit and loss for each asset, ordered by symbol
+    pub assets: Vec<AssetPnl>,
+}
+
+impl PnlReport {
+    /// Build the report out of the user's trades and asset wallets, with the current prices in EUR by symbol.
+    ///
+    /// Prices can be obtained from the ticker with [`crate::model::Ticker::prices_in`] or from the
+    /// last close of an OHLC chart with [`crate::model::OpenHighLowCloseChart::last_close`].
+    ///
+    /// The holding comes from the wallets, so assets received outside of trades (e.g. deposits)
+    /// are valued, but they don't contribute to the invested amount
+    pub fn new(
+        trades: &[Trade],
+        asset_wallets: &[AssetWallet],
+        prices_eur: &HashMap<String, Decimal>,
+        method: CostBasisMethod,
+    ) -> Self {
+        let cost_basis = CostBasis::compute(trades, method);
+
+        let mut holdings: HashMap<&str, Decimal> = HashMap::new();
+        for wallet in asset_wallets.iter().filter(|wallet| !wallet.deleted) {
+            *holdings
+                .entry(wallet.asset_symbol.as_str())
+                .or_insert(Decimal::ZERO) += wallet.balance;
+        }
+
+        let symbols: BTreeSet<&str> = cost_basis
+            .open_lots
+            .iter()
+            .map(|lot| lot.symbol.as_str())
+            .chain(
+                cost_basis
+                    .disposals
+                    .iter()
+                    .map(|disposal| disposal.symbol.as_str()),
+            )
+            .chain(
+                holdings
+                    .iter()
+                    .filter(|(_, balance)| !balance.is_zero())
+                    .map(|(symbol, _)| *symbol),
+            )
+            .collect();
+
+        let assets = symbols
+            .into_iter()
+            .map(|symbol| {
+                let invested_eur = cost_basis.invested_eur(symbol);
+                let holding = holdings.get(symbol).copied().unwrap_or_default();
+                let price_eur = prices_eur.get(symbol).copied();
+                let current_value_eur = price_eur.map(|price| price * holding);
+                AssetPnl {
+                    symbol: symbol.to_string(),
+                    invested_eur,
+                    holding,
+                    price_eur,
+                    current_value_eur,
+                    realized_gain_eur: cost_basis.realized_gain_eur(symbol),
+                    unrealized_gain_eur: current_value_eur.map(|value| value - invested_eur),
+                }
+            })
+            .collect();
+
+        Self { method, assets }
+    }
+
+    /// Get the profit and loss for `symbol`
+    pub fn asset(&self, symbol: &str) -> Option<&AssetPnl> {
+        self.assets.iter().find(|asset| asset.symbol == symbol)
+    }
+
+    /// Total cost of the lots still held
+    pub fn total_invested_eur(&self) -> Decimal {
+        self.assets.iter().map(|asset| asset.invested_eur).sum()
+    }
+
+    /// Total gain realized by the sells
+    pub fn total_realized_gain_eur(&self) -> Decimal {
+        self.assets
+            .iter()
+            .map(|asset| asset.realized_gain_eur)
+            .sum()
+    }
+
+    /// Total unrealized gain of the assets with a known price
+    pub fn total_unrealized_gain_eur(&self) -> Decimal {
+        self.assets
+            .iter()
+            .filter_map(|asset| asset.unrealized_gain_eur)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{AssetClass, TradeType};
+    use crate::testing::fixture::{datetime, TradeBuilder};
+
+    use pretty_assertions::assert_eq;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn should_build_pnl_report() {
+        let trades = vec![
+            TradeBuilder::new("3")
+                .r#type(TradeType::Sell)
+                .datetime(datetime("2023-03-01T12:00:00+00:00"))
+                .asset("BTC-id", "BTC")
+                .amounts(dec!(1), dec!(300))
+                .build(),
+            TradeBuilder::new("2")
+                .r#type(TradeType::Buy)
+                .datetime(datetime("2023-02-01T12:00:00+00:00"))
+                .asset("ETH-id", "ETH")
+                .amounts(dec!(10), dec!(1000))
+                .build(),
+            TradeBuilder::new("1")
+                .r#type(TradeType::Buy)
+                .datetime(datetime("2023-01-01T12:00:00+00:00"))
+                .asset("BTC-id", "BTC")
+                .amounts(dec!(2), dec!(200))
+                .build(),
+        ];
+        let wallets = vec![
+            wallet("BTC", dec!(1)),
+            wallet("ETH", dec!(10)),
+            wallet("XAU", dec!(5)),
+        ];
+        let prices = HashMap::from([
+            ("BTC".to_string(), dec!(400)),
+            ("ETH".to_string(), dec!(90)),
+        ]);
+
+        let report = PnlReport::new(&trades, &wallets, &prices, CostBasisMethod::Fifo);
+
+        assert_eq!(
+            report
+                .assets
+                .iter()
+                .map(|asset| asset.symbol.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["BTC", "ETH", "XAU"]
+        );
+        let btc = report.asset("BTC").unwrap();
+        assert_eq!(btc.invested_eur, dec!(100));
+        assert_eq!(btc.holding, dec!(1));
+        assert_eq!(btc.current_value_eur, Some(dec!(400)));
+        assert_eq!(btc.realized_gain_eur, dec!(200));
+        assert_eq!(btc.unrealized_gain_eur, Some(dec!(300)));
+        let eth = report.asset("ETH").unwrap();
+        assert_eq!(eth.unrealized_gain_eur, Some(dec!(-100)));
+        let xau = report.asset("XAU").unwrap();
+        assert_eq!(xau.invested_eur, Decimal::ZERO);
+        assert_eq!(xau.price_eur, None);
+        assert_eq!(xau.unrealized_gain_eur, None);
+
+        assert_eq!(report.total_invested_eur(), dec!(1100));
+        assert_eq!(report.total_realized_gain_eur(), dec!(200));
+        assert_eq!(report.total_unrealized_gain_eur(), dec!(200));
+    }
+
+    fn wallet(symbol: &str, balance: Decimal) -> AssetWallet {
+        AssetWallet {
+            asset_id: format!("{symbol}-id"),
+            asset_symbol: symbol.to_string(),
+            balance,
+            class: AssetClass::Cryptocurrency,
+            deleted: false,
+            id: format!("{symbol}-wallet"),
+            is_default: true,
+            name: format!("{symbol} wallet"),
+        }
+    }
+}
@@ -0,0 +1,642 @@
+//! # Tax
+//!
+//! Capital gains, fees and income per fiscal year, according to the rules of a jurisdiction.
+//!
+//! The built-in jurisdictions only model the cost basis method and the holding period exemptions,
+//! which are the rules needed to compute the taxable gains; tax rates, allowances and thresholds are left to the caller.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Datelike, FixedOffset, Months, NaiveDate};
+use rust_decimal::Decimal;
+
+use super::{CostBasis, CostBasisMethod};
+use crate::model::crypto_wallet::CryptoWalletTransaction;
+use crate::model::fiat_wallet::FiatWalletTransaction;
+use crate::model::{AssetClass, Trade, TransactionStatus};
+
+/// Tax rules of a jurisdiction
+pub trait Jurisdiction {
+    /// Cost basis method used to match sells against buys
+    fn cost_basis_method(&self) -> CostBasisMethod;
+
+    /// Returns whether the gain of an asset of class `class`, acquired at `acquired` and sold at `disposed`, is tax exempt
+    fn is_exempt(
+        &self,
+        class: AssetClass,
+        acquired: DateTime<FixedOffset>,
+        disposed: DateTime<FixedOffset>,
+    ) -> bool;
+
+    /// Fiscal year `datetime` belongs to. Defaults to the calendar year
+    fn fiscal_year(&self, datetime: DateTime<FixedOffset>) -> i32 {
+        datetime.year()
+    }
+
+    /// Returns whether the finished crypto wallet transaction `tx` is income, reported at its value in EUR.
+    /// Defaults to no transaction, since Bitpanda doesn't tell rewards apart from other transfers
+    fn is_income(&self, _tx: &CryptoWalletTransaction) -> bool {
+        false
+    }
+}
+
+/// A holding period after which a gain becomes tax exempt
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct HoldingPeriod {
+    months: u32,
+}
+
+impl HoldingPeriod {
+    /// Gains are exempt after the asset has been held for `months` months
+    pub fn months(months: u32) -> Self {
+        Self { months }
+    }
+
+    /// Gains are exempt after the asset has been held for one year
+    pub fn one_year() -> Self {
+        Self::months(12)
+    }
+
+    /// Returns whether the holding period has elapsed between `acquired` and `disposed`
+    pub fn elapsed(
+        &self,
+        acquired: DateTime<FixedOffset>,
+        disposed: DateTime<FixedOffset>,
+    ) -> bool {
+        acquired
+            .checked_add_months(Months::new(self.months))
+            .is_some_and(|exempt_from| disposed > exempt_from)
+    }
+}
+
+/// Jurisdiction with a cost basis method and an optional holding period exemption for all the assets
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Custom {
+    pub method: CostBasisMethod,
+    pub holding_period: Option<HoldingPeriod>,
+}
+
+impl Jurisdiction for Custom {
+    fn cost_basis_method(&self) -> CostBasisMethod {
+        self.method
+    }
+
+    fn is_exempt(
+        &self,
+        _class: AssetClass,
+        acquired: DateTime<FixedOffset>,
+        disposed: DateTime<FixedOffset>,
+    ) -> bool {
+        self.holding_period
+            .is_some_and(|period| period.elapsed(acquired, disposed))
+    }
+}
+
+/// Austria.
+///
+/// Gains are computed with the moving average cost. Cryptocurrencies acquired before 2021-03-01
+/// and securities acquired before 2011-01-01 are exempt once held for more than one year;
+/// metals are exempt after one year.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Austria;
+
+impl Jurisdiction for Austria {
+    fn cost_basis_method(&self) -> CostBasisMethod {
+        CostBasisMethod::Average
+    }
+
+    fn is_exempt(
+        &self,
+        class: AssetClass,
+        acquired: DateTime<FixedOffset>,
+        disposed: DateTime<FixedOffset>,
+    ) -> bool {
+        let old_assets_until = match class {
+            AssetClass::Cryptocurrency | AssetClass::Cryptoindex => date(2021, 3, 1),
+            AssetClass::Commodity | AssetClass::Etf | AssetClass::Stock => date(2011, 1, 1),
+            AssetClass::Metal => return HoldingPeriod::one_year().elapsed(acquired, disposed),
+        };
+
+        acquired.date_naive() < old_assets_until
+            && HoldingPeriod::one_year().elapsed(acquired, disposed)
+    }
+}
+
+/// Germany.
+///
+/// Gains are computed with FIFO. Cryptocurrencies and metals are exempt once held for more than one year,
+/// while gains on securities are never exempt.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Germany;
+
+impl Jurisdiction for Germany {
+    fn cost_basis_method(&self) -> CostBasisMethod {
+        CostBasisMethod::Fifo
+    }
+
+    fn is_exempt(
+        &self,
+        class: AssetClass,
+        acquired: DateTime<FixedOffset>,
+        disposed: DateTime<FixedOffset>,
+    ) -> bool {
+        match class {
+            AssetClass::Cryptocurrency | AssetClass::Cryptoindex | AssetClass::Metal => {
+                HoldingPeriod::one_year().elapsed(acquired, disposed)
+            }
+            AssetClass::Commodity | AssetClass::Etf | AssetClass::Stock => false,
+        }
+    }
+}
+
+/// Italy.
+///
+/// Gains are computed with LIFO and are never exempt.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Italy;
+
+impl Jurisdiction for Italy {
+    fn cost_basis_method(&self) -> CostBasisMethod {
+        CostBasisMethod::Lifo
+    }
+
+    fn is_exempt(
+        &self,
+        _class: AssetClass,
+        _acquired: DateTime<FixedOffset>,
+        _disposed: DateTime<FixedOffset>,
+    ) -> bool {
+        false
+    }
+}
+
+/// Describes what a tax line reports
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum TaxLineKind {
+    /// Gain (or loss, if negative) realized by a sell
+    CapitalGain,
+    /// Fee paid on a transaction
+    Fee,
+    /// Asset received as income (e.g. rewards)
+    Income,
+}
+
+/// An entry of the tax report
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TaxLine {
+    pub kind: TaxLineKind,
+    pub datetime: DateTime<FixedOffset>,
+    /// Symbol of the traded asset, or the Bitpanda cryptocoin/fiat id for wallet transactions,
+    /// since transactions don't carry the symbol
+    pub asset: String,
+    /// Id of the trade or transaction which originated the line
+    pub reference_id: String,
+    /// Amount of the asset
+    pub amount: Decimal,
+    /// Value in EUR of the line; negative for capital losses
+    pub value_eur: Decimal,
+    /// Whether the line is tax exempt
+    pub exempt: bool,
+}
+
+/// Summary of a fiscal year
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FiscalYear {
+    pub year: i32,
+    /// Taxable capital gains
+    pub gains_eur: Decimal,
+    /// Taxable capital losses, as a positive amount
+    pub losses_eur: Decimal,
+    /// Net capital gains which are exempt
+    pub exempt_gains_eur: Decimal,
+    pub fees_eur: Decimal,
+    pub income_eur: Decimal,
+    /// Lines of the year, ordered by time
+    pub lines: Vec<TaxLine>,
+}
+
+impl FiscalYear {
+    fn new(year: i32) -> Self {
+        Self {
+            year,
+            gains_eur: Decimal::ZERO,
+            losses_eur: Decimal::ZERO,
+            exempt_gains_eur: Decimal::ZERO,
+            fees_eur: Decimal::ZERO,
+            income_eur: Decimal::ZERO,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Taxable gains minus taxable losses
+    pub fn net_gains_eur(&self) -> Decimal {
+        self.gains_eur - self.losses_eur
+    }
+
+    fn push(&mut self, line: TaxLine) {
+        match (line.kind, line.exempt) {
+            (TaxLineKind::CapitalGain, true) => self.exempt_gains_eur += line.value_eur,
+            (TaxLineKind::CapitalGain, false) if line.value_eur.is_sign_negative() => {
+                self.losses_eur -= line.value_eur
+            }
+            (TaxLineKind::CapitalGain, false) => self.gains_eur += line.value_eur,
+            (TaxLineKind::Fee, _) => self.fees_eur += line.value_eur,
+            (TaxLineKind::Income, _) => self.income_eur += line.value_eur,
+        }
+        self.lines.push(line);
+    }
+}
+
+/// Tax report of the user's history, per fiscal year
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TaxReport {
+    pub method: CostBasisMethod,
+    /// Fiscal years, from the oldest
+    pub years: Vec<FiscalYear>,
+}
+
+impl TaxReport {
+    /// Get a builder for a tax report according to the rules of `jurisdiction`
+    pub fn builder(jurisdiction: &dyn Jurisdiction) -> TaxReportBuilder<'_> {
+        TaxReportBuilder {
+            jurisdiction,
+            method: None,
+            trades: &[],
+            crypto_transactions: &[],
+            fiat_transactions: &[],
+            asset_classes: HashMap::new(),
+        }
+    }
+
+    /// Get the summary of `year`
+    pub fn year(&self, year: i32) -> Option<&FiscalYear> {
+        self.years
+            .iter()
+            .find(|fiscal_year| fiscal_year.year == year)
+    }
+}
+
+/// Tax report builder
+pub struct TaxReportBuilder<'a> {
+    jurisdiction: &'a dyn Jurisdiction,
+    method: Option<CostBasisMethod>,
+    trades: &'a [Trade],
+    crypto_transactions: &'a [CryptoWalletTransaction],
+    fiat_transactions: &'a [FiatWalletTransaction],
+    asset_classes: HashMap<String, AssetClass>,
+}
+
+impl<'a> TaxReportBuilder<'a> {
+    /// Override the cost basis method of the jurisdiction
+    pub fn cost_basis_method(mut self, method: CostBasisMethod) -> Self {
+        self.method = Some(method);
+
+        self
+    }
+
+    /// Trades to compute the capital gains from
+    pub fn trades(mut self, trades: &'a [Trade]) -> Self {
+        self.trades = trades;
+
+        self
+    }
+
+    /// Crypto wallet transactions to compute fees and income from.
+    /// Which transactions are income is up to [`Jurisdiction::is_income`]
+    pub fn crypto_transactions(mut self, transactions: &'a [CryptoWalletTransaction]) -> Self {
+        self.crypto_transactions = transactions;
+
+        self
+    }
+
+    /// Fiat wallet transactions to compute fees from
+    pub fn fiat_transactions(mut self, transactions: &'a [FiatWalletTransaction]) -> Self {
+        self.fiat_transactions = transactions;
+
+        self
+    }
+
+    /// Set the asset class of each symbol, e.g. from the user's asset wallets.
+    /// Symbols without a class are treated as cryptocurrencies
+    pub fn asset_classes(
+        mut self,
+        classes: impl IntoIterator<Item = (String, AssetClass)>,
+    ) -> Self {
+        self.asset_classes = classes.into_iter().collect();
+
+        self
+    }
+
+    /// Build the report
+    pub fn build(self) -> TaxReport {
+        let method = self
+            .method
+            .unwrap_or_else(|| self.jurisdiction.cost_basis_method());
+        let mut years: BTreeMap<i32, FiscalYear> = BTreeMap::new();
+        let mut push = |line: TaxLine| {
+            let year = self.jurisdiction.fiscal_year(line.datetime);
+            years
+                .entry(year)
+                .or_insert_with(|| FiscalYear::new(year))
+                .push(line);
+        };
+
+        let cost_basis = CostBasis::compute(self.trades, method);
+        for disposal in cost_basis.disposals.iter() {
+            let class = self
+                .asset_classes
+                .get(&disposal.symbol)
+                .copied()
+                .unwrap_or(AssetClass::Cryptocurrency);
+            let proceeds_of = |amount: Decimal| {
+                if disposal.amount.is_zero() {
+                    Decimal::ZERO
+                } else {
+                    disposal.proceeds_eur * amount / disposal.amount
+                }
+            };
+            for lot in disposal.lots.iter() {
+                push(TaxLine {
+                    kind: TaxLineKind::CapitalGain,
+                    datetime: disposal.datetime,
+                    asset: disposal.symbol.clone(),
+                    reference_id: disposal.trade_id.clone(),
+                    amount: lot.amount,
+                    value_eur: proceeds_of(lot.amount) - lot.cost_eur,
+                    exempt: self
+                        .jurisdiction
+                        .is_exempt(class, lot.acquired, disposal.datetime),
+                });
+            }
+            if !disposal.uncovered_amount.is_zero() {
+                push(TaxLine {
+                    kind: TaxLineKind::CapitalGain,
+                    datetime: disposal.datetime,
+                    asset: disposal.symbol.clone(),
+                    reference_id: disposal.trade_id.clone(),
+                    amount: disposal.uncovered_amount,
+                    value_eur: proceeds_of(disposal.uncovered_amount),
+                    exempt: false,
+                });
+            }
+        }
+
+        for tx in self
+            .crypto_transactions
+            .iter()
+            .filter(|tx| tx.status == TransactionStatus::Finished)
+        {
+            let unit_value_eur = if tx.amount.is_zero() {
+                Decimal::ZERO
+            } else {
+                tx.amount_eur / tx.amount
+            };
+            if !tx.fee.is_zero() {
+                push(TaxLine {
+                    kind: TaxLineKind::Fee,
+                    datetime: tx.datetime,
+                    asset: tx.cryptocoin_id.clone(),
+                    reference_id: tx.id.clone(),
+                    amount: tx.fee,
+                    value_eur: tx.fee * unit_value_eur,
+                    exempt: false,
+                });
+            }
+            if self.jurisdiction.is_income(tx) {
+                push(TaxLine {
+                    kind: TaxLineKind::Income,
+                    datetime: tx.datetime,
+                    asset: tx.cryptocoin_id.clone(),
+                    reference_id: tx.id.clone(),
+                    amount: tx.amount,
+                    value_eur: tx.amount_eur,
+                    exempt: false,
+                });
+            }
+        }
+
+        for tx in self
+            .fiat_transactions
+            .iter()
+            .filter(|tx| tx.status == TransactionStatus::Finished && !tx.fee.is_zero())
+        {
+            push(TaxLine {
+                kind: TaxLineKind::Fee,
+                datetime: tx.datetime,
+                asset: tx.fiat_id.clone(),
+                reference_id: tx.id.clone(),
+                amount: tx.fee,
+                value_eur: tx.fee * tx.to_eur_rate,
+                exempt: false,
+            });
+        }
+
+        let mut years: Vec<FiscalYear> = years.into_values().collect();
+        for year in years.iter_mut() {
+            year.lines.sort_by_key(|line| line.datetime);
+        }
+
+        TaxReport { method, years }
+    }
+}
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{InOrOut, TradeType, TransactionType};
+    use crate::testing::fixture::{CryptoWalletTransactionBuilder, TradeBuilder};
+
+    use pretty_assertions::assert_eq;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn should_check_holding_period() {
+        let period = HoldingPeriod::one_year();
+        assert!(period.elapsed(datetime("2021-01-10"), datetime("2022-01-11")));
+        assert!(!period.elapsed(datetime("2021-01-10"), datetime("2022-01-10")));
+        assert!(!period.elapsed(datetime("2021-01-10"), datetime("2021-06-10")));
+    }
+
+    #[test]
+    fn should_apply_german_rules() {
+        let report = TaxReport::builder(&Germany).trades(&trades()).build();
+
+        assert_eq!(report.method, CostBasisMethod::Fifo);
+        assert_eq!(report.years.len(), 1);
+        let year = report.year(2022).unwrap();
+        // lot 1 held for more than a year -> exempt; lot 2 -> taxable
+        assert_eq!(year.exempt_gains_eur, dec!(200));
+        assert_eq!(year.gains_eur, dec!(100));
+        assert_eq!(year.losses_eur, Decimal::ZERO);
+        assert_eq!(year.lines.len(), 2);
+    }
+
+    #[test]
+    fn should_not_exempt_german_securities() {
+        let report = TaxReport::builder(&Germany)
+            .trades(&trades())
+            .asset_classes([("BTC".to_string(), AssetClass::Stock)])
+            .build();
+
+        let year = report.year(2022).unwrap();
+        assert_eq!(year.exempt_gains_eur, Decimal::ZERO);
+        assert_eq!(year.gains_eur, dec!(300));
+    }
+
+    #[test]
+    fn should_apply_italian_rules() {
+        let report = TaxReport::builder(&Italy).trades(&trades()).build();
+
+        assert_eq!(report.method, CostBasisMethod::Lifo);
+        let year = report.year(2022).unwrap();
+        assert_eq!(year.exempt_gains_eur, Decimal::ZERO);
+        assert_eq!(year.net_gains_eur(), dec!(300));
+    }
+
+    #[test]
+    fn should_apply_austrian_rules() {
+        // lot 1 acquired before 2021-03-01 and held for more than a year
+        let report = TaxReport::builder(&Austria)
+            .trades(&trades())
+            .cost_basis_method(CostBasisMethod::Fifo)
+            .build();
+
+        let year = report.year(2022).unwrap();
+        assert_eq!(year.exempt_gains_eur, dec!(200));
+        assert_eq!(year.gains_eur, dec!(100));
+    }
+
+    #[test]
+    fn should_report_losses_fees_and_income() {
+        let trades = vec![
+            TradeBuilder::new("1")
+                .r#type(TradeType::Buy)
+                .datetime(datetime("2022-01-01"))
+                .amounts(dec!(1), dec!(500))
+                .build(),
+            TradeBuilder::new("2")
+                .r#type(TradeType::Sell)
+                .datetime(datetime("2022-06-01"))
+                .amounts(dec!(1), dec!(300))
+                .build(),
+        ];
+        let crypto_txs = vec![
+            crypto_tx("3", TransactionType::Transfer).build(),
+            crypto_tx("4", TransactionType::Withdrawal)
+                .fee(dec!(0.1))
+                .build(),
+        ];
+        let fiat_txs = vec![FiatWalletTransaction {
+            amount: dec!(100),
+            datetime: datetime("2023-01-01"),
+            fee: dec!(2),
+            fiat_id: "1".to_string(),
+            id: "5".to_string(),
+            in_or_out: InOrOut::Outgoing,
+            status: TransactionStatus::Finished,
+            to_eur_rate: dec!(0.5),
+            transaction_type: TransactionType::Withdrawal,
+            user_id: "user".to_string(),
+            wallet_id: "wallet".to_string(),
+        }];
+
+        let report = TaxReport::builder(&RewardsAsIncome)
+            .trades(&trades)
+            .crypto_transactions(&crypto_txs)
+            .fiat_transactions(&fiat_txs)
+            .build();
+
+        let year = report.year(2022).unwrap();
+        assert_eq!(year.losses_eur, dec!(200));
+        assert_eq!(year.net_gains_eur(), dec!(-200));
+        assert_eq!(year.income_eur, dec!(20));
+        assert_eq!(year.fees_eur, dec!(2));
+        let year = report.year(2023).unwrap();
+        assert_eq!(year.fees_eur, dec!(1));
+    }
+
+    #[test]
+    fn should_report_income_according_to_jurisdiction() {
+        let crypto_txs = vec![
+            crypto_tx("1", TransactionType::Transfer).build(),
+            crypto_tx("2", TransactionType::Transfer)
+                .in_or_out(InOrOut::Outgoing)
+                .build(),
+            crypto_tx("3", TransactionType::Deposit).build(),
+        ];
+
+        let report = TaxReport::builder(&RewardsAsIncome)
+            .crypto_transactions(&crypto_txs)
+            .build();
+        let year = report.year(2022).unwrap();
+        assert_eq!(year.income_eur, dec!(20));
+        assert_eq!(year.lines.len(), 1);
+        assert_eq!(year.lines[0].kind, TaxLineKind::Income);
+        assert_eq!(year.lines[0].reference_id, "1");
+
+        // no transaction is income by default
+        let report = TaxReport::builder(&Germany)
+            .crypto_transactions(&crypto_txs)
+            .build();
+        assert!(report.years.is_empty());
+    }
+
+    /// FIFO without exemptions, reporting incoming transfers as income
+    struct RewardsAsIncome;
+
+    impl Jurisdiction for RewardsAsIncome {
+        fn cost_basis_method(&self) -> CostBasisMethod {
+            CostBasisMethod::Fifo
+        }
+
+        fn is_exempt(
+            &self,
+            _class: AssetClass,
+            _acquired: DateTime<FixedOffset>,
+            _disposed: DateTime<FixedOffset>,
+        ) -> bool {
+            false
+        }
+
+        fn is_income(&self, tx: &CryptoWalletTransaction) -> bool {
+            tx.transaction_type == TransactionType::Transfer && tx.in_or_out == InOrOut::Incoming
+        }
+    }
+
+    /// buy 1 BTC for 100 (2021-01-01), buy 1 BTC for 200 (2022-01-01), sell 2 BTC for 600 (2022-06-01)
+    fn trades() -> Vec<Trade> {
+        vec![
+            TradeBuilder::new("1")
+                .r#type(TradeType::Buy)
+                .datetime(datetime("2021-01-01"))
+                .amounts(dec!(1), dec!(100))
+                .build(),
+            TradeBuilder::new("2")
+                .r#type(TradeType::Buy)
+                .datetime(datetime("2022-01-01"))
+                .amounts(dec!(1), dec!(200))
+                .build(),
+            TradeBuilder::new("3")
+                .r#type(TradeType::Sell)
+                .datetime(datetime("2022-06-01"))
+                .amounts(dec!(2), dec!(600))
+                .build(),
+        ]
+    }
+
+    fn crypto_tx(id: &str, transaction_type: TransactionType) -> CryptoWalletTransactionBuilder {
+        CryptoWalletTransactionBuilder::new(id)
+            .transaction_type(transaction_type)
+            .datetime(datetime("2022-03-01"))
+            .amount(dec!(1), dec!(20))
+    }
+
+    fn datetime(date: &str) -> DateTime<FixedOffset> {
+        format!("{date}T12:00:00+00:00").parse().unwrap()
+    }
+}
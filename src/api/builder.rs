@@ -0,0 +1,199 @@
+//! # Client builder
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::cassette::{RecordingTransport, ReplayTransport};
+use super::rate_limit::RateLimiter;
+use super::{Client, RateLimit, ReqwestTransport, RetryPolicy, Transport};
+
+const BITPANDA_API_URL: &str = "https://api.bitpanda.com/v1";
+const BITPANDA_PUBLIC_URL: &str = "https://api.bitpanda.com/v3";
+
+/// Bitpanda api client builder
+#[derive(Clone)]
+pub struct ClientBuilder {
+    x_apikey: Option<String>,
+    api_url: String,
+    public_url: String,
+    transport: Option<Arc<dyn Transport>>,
+    retry_policy: RetryPolicy,
+    private_rate_limit: Option<RateLimit>,
+    public_rate_limit: Option<RateLimit>,
+    cassette: Option<CassetteMode>,
+}
+
+/// How the cassette set on the builder is used
+#[derive(Debug, Clone)]
+enum CassetteMode {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self {
+            x_apikey: None,
+            api_url: BITPANDA_API_URL.to_string(),
+            public_url: BITPANDA_PUBLIC_URL.to_string(),
+            transport: None,
+            retry_policy: RetryPolicy::default(),
+            private_rate_limit: None,
+            public_rate_limit: None,
+            cassette: None,
+        }
+    }
+}
+
+impl fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientBuilder")
+            // never print the secret
+            .field("x_apikey", &self.x_apikey.as_ref().map(|_| "<redacted>"))
+            .field("api_url", &self.api_url)
+            .field("public_url", &self.public_url)
+            .field("custom_transport", &self.transport.is_some())
+            .field("retry_policy", &self.retry_policy)
+            .field("private_rate_limit", &self.private_rate_limit)
+            .field("public_rate_limit", &self.public_rate_limit)
+            .field("cassette", &self.cassette)
+            .finish()
+    }
+}
+
+impl ClientBuilder {
+    /// Set x-apikey for the private API
+    pub fn x_apikey(mut self, apikey: impl ToString) -> Self {
+        self.x_apikey = Some(apikey.to_string());
+
+        self
+    }
+
+    /// Override the base URL for the private v1 API (default: `https://api.bitpanda.com/v1`)
+    pub fn api_url(mut self, url: impl ToString) -> Self {
+        self.api_url = url.to_string().trim_end_matches('/').to_string();
+
+        self
+    }
+
+    /// Override the base URL for the public v3 API (default: `https://api.bitpanda.com/v3`)
+    pub fn public_url(mut self, url: impl ToString) -> Self {
+        self.public_url = url.to_string().trim_end_matches('/').to_string();
+
+        self
+    }
+
+    /// Use the provided http client to perform requests.
+    /// Use this to configure proxies, root certificates and timeouts.
+    /// If not set, a default [`reqwest::Client`] is used
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.transport = Some(Arc::new(ReqwestTransport::new(client)));
+
+        self
+    }
+
+    /// Send the requests through the provided transport instead of [`reqwest`].
+    /// Overrides [`Self::http_client`]
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+
+        self
+    }
+
+    /// Set the retry policy applied to each request (default: never retry)
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+
+        self
+    }
+
+    /// Limit the requests sent to the private v1 API.
+    /// The quota is shared by all the clones of the built client
+    pub fn private_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.private_rate_limit = Some(limit);
+
+        self
+    }
+
+    /// Limit the requests sent to the public v3 API and to the v1 ticker, which requires no api key.
+    /// The quota is shared by all the clones of the built client
+    pub fn public_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.public_rate_limit = Some(limit);
+
+        self
+    }
+
+    /// Record every request and its raw response to the cassette at `path`, which is rewritten
+    /// after each interaction. The api key is scrubbed from the recording
+    pub fn record(mut self, path: impl AsRef<Path>) -> Self {
+        self.cassette = Some(CassetteMode::Record(path.as_ref().to_path_buf()));
+
+        self
+    }
+
+    /// Serve the responses recorded in the cassette at `path` instead of hitting the network.
+    /// Requests which were not recorded fail with [`super::ApiError::Cassette`].
+    /// The private API still requires an api key to be set, but any key will do
+    pub fn replay(mut self, path: impl AsRef<Path>) -> Self {
+        self.cassette = Some(CassetteMode::Replay(path.as_ref().to_path_buf()));
+
+        self
+    }
+
+    /// Build the client
+    pub fn build(self) -> Client {
+        let transport = self
+            .transport
+            .unwrap_or_else(|| Arc::new(ReqwestTransport::default()));
+        let transport: Arc<dyn Transport> = match self.cassette {
+            Some(CassetteMode::Record(path)) => Arc::new(RecordingTransport::new(transport, path)),
+            Some(CassetteMode::Replay(path)) => Arc::new(ReplayTransport::new(path)),
+            None => transport,
+        };
+
+        Client {
+            x_apikey: self.x_apikey,
+            api_url: self.api_url,
+            public_url: self.public_url,
+            transport,
+            retry_policy: self.retry_policy,
+            private_rate_limiter: self.private_rate_limit.map(RateLimiter::new),
+            public_rate_limiter: self.public_rate_limit.map(RateLimiter::new),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_redact_apikey_in_debug() {
+        let debug = format!("{:?}", ClientBuilder::default().x_apikey("secret-key"));
+        assert!(!debug.contains("secret-key"));
+        assert!(debug.contains(r#"x_apikey: Some("<redacted>")"#));
+    }
+
+    #[test]
+    fn should_build_client_with_default_urls() {
+        let client = ClientBuilder::default().build();
+        assert!(client.x_apikey.is_none());
+        assert_eq!(client.api_url, BITPANDA_API_URL);
+        assert_eq!(client.public_url, BITPANDA_PUBLIC_URL);
+    }
+
+    #[test]
+    fn should_build_client_with_custom_urls() {
+        let client = ClientBuilder::default()
+            .x_apikey("secret")
+            .api_url("http://localhost:8080/v1/")
+            .public_url("http://localhost:8081/v3")
+            .build();
+        assert_eq!(client.x_apikey.as_deref(), Some("secret"));
+        assert_eq!(client.api_url, "http://localhost:8080/v1");
+        assert_eq!(client.public_url, "http://localhost:8081/v3");
+    }
+}
@@ -0,0 +1,289 @@
+//! # Cassette
+//!
+//! Recording and replay of the interactions with the Bitpanda API
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use futures_util::future::BoxFuture;
+use reqwest::header::RETRY_AFTER;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use super::transport::{HttpRequest, HttpResponse, Transport};
+use super::{ApiError, ApiResult};
+
+/// Placeholder the api key is replaced with in the recorded interactions
+const REDACTED: &str = "[REDACTED]";
+
+/// Interactions recorded with [`super::ClientBuilder::record`]
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+/// A request and the raw response received
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    /// Requested URL
+    pub url: String,
+    /// Status of the response
+    pub status: u16,
+    /// Value of the `Retry-After` header in seconds
+    pub retry_after: Option<u64>,
+    /// Body of the response
+    pub body: String,
+}
+
+impl Cassette {
+    /// Load a cassette from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> ApiResult<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path).map_err(|err| {
+            ApiError::Cassette(format!("failed to read {}: {err}", path.display()))
+        })?;
+
+        serde_json::from_str(&json).map_err(Into::into)
+    }
+
+    /// Save the cassette to a JSON file
+    pub fn save(&self, path: impl AsRef<Path>) -> ApiResult<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self)?;
+
+        fs::write(path, json)
+            .map_err(|err| ApiError::Cassette(format!("failed to write {}: {err}", path.display())))
+    }
+}
+
+/// Transport which records the interactions of the wrapped transport,
+/// rewriting the cassette file after each of them.
+/// The api key sent with the requests is scrubbed from the recording
+#[derive(Clone)]
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    path: PathBuf,
+    cassette: Arc<Mutex<Cassette>>,
+}
+
+impl RecordingTransport {
+    pub fn new(inner: Arc<dyn Transport>, path: impl AsRef<Path>) -> Self {
+        Self {
+            inner,
+            path: path.as_ref().to_path_buf(),
+            cassette: Arc::default(),
+        }
+    }
+
+    fn record(&self, request: &HttpRequest, response: &HttpResponse) -> ApiResult<()> {
+        let scrub = |s: &str| match request.header("X-API-KEY") {
+            Some(key) if !key.is_empty() => s.replace(key, REDACTED),
+            _ => s.to_string(),
+        };
+        let mut cassette = self.cassette.lock().unwrap();
+        cassette.interactions.push(Interaction {
+            url: scrub(&request.url),
+            status: response.status,
+            retry_after: response
+                .header(RETRY_AFTER.as_str())
+                .and_then(|value| value.trim().parse().ok()),
+            body: scrub(&response.body),
+        });
+        trace!("recorded interaction for {}", request.url);
+
+        cassette.save(&self.path)
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, ApiResult<HttpResponse>> {
+        Box::pin(async move {
+            let response = self.inner.send(request.clone()).await?;
+            self.record(&request, &response)?;
+
+            Ok(response)
+        })
+    }
+}
+
+/// Transport which replays the interactions of a cassette, loaded on the first request.
+/// Each interaction is replayed once, in the recorded order for requests to the same URL.
+/// Only the path and the query are compared, so a cassette can be replayed against any host
+#[derive(Debug, Clone)]
+pub struct ReplayTransport {
+    path: PathBuf,
+    state: Arc<Mutex<Option<ReplayState>>>,
+}
+
+#[derive(Debug)]
+struct ReplayState {
+    interactions: Vec<Interaction>,
+    played: Vec<bool>,
+}
+
+impl ReplayTransport {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            state: Arc::default(),
+        }
+    }
+
+    /// Get the first interaction recorded for `url` which hasn't been replayed yet
+    fn play(&self, url: &str) -> ApiResult<Interaction> {
+        let url = Url::parse(url).map_err(|err| ApiError::Cassette(format!("{url}: {err}")))?;
+        let mut state = self.state.lock().unwrap();
+        if state.is_none() {
+            let interactions = Cassette::load(&self.path)?.interactions;
+            *state = Some(ReplayState {
+                played: vec![false; interactions.len()],
+                interactions,
+            });
+        }
+        let state = state.as_mut().unwrap();
+
+        let position = state
+            .interactions
+            .iter()
+            .zip(state.played.iter())
+            .position(|(interaction, played)| {
+                !played
+                    && Url::parse(&interaction.url)
+                        .is_ok_and(|recorded| same_resource(&recorded, &url))
+            })
+            .ok_or_else(|| ApiError::Cassette(format!("no interaction left for {url}")))?;
+        state.played[position] = true;
+        trace!("replaying interaction for {url}");
+
+        Ok(state.interactions[position].clone())
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, ApiResult<HttpResponse>> {
+        Box::pin(async move {
+            let interaction = self.play(&request.url)?;
+
+            Ok(HttpResponse {
+                status: interaction.status,
+                headers: interaction
+                    .retry_after
+                    .map(|secs| (RETRY_AFTER.to_string(), secs.to_string()))
+                    .into_iter()
+                    .collect(),
+                body: interaction.body,
+            })
+        })
+    }
+}
+
+fn same_resource(a: &Url, b: &Url) -> bool {
+    a.path() == b.path() && a.query() == b.query()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    /// Transport echoing the url as body
+    struct Echo;
+
+    impl Transport for Echo {
+        fn send(&self, request: HttpRequest) -> BoxFuture<'_, ApiResult<HttpResponse>> {
+            Box::pin(async move {
+                Ok(HttpResponse {
+                    status: 429,
+                    headers: vec![("retry-after".to_string(), "30".to_string())],
+                    body: format!("{{\"url\":\"{}\"}}", request.url),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn should_record_and_scrub_api_key() {
+        let path = tmp_path();
+        let transport = RecordingTransport::new(Arc::new(Echo), &path);
+        let response = transport
+            .send(HttpRequest {
+                url: "http://localhost/v1/trades?key=secret".to_string(),
+                headers: vec![("X-API-KEY".to_string(), "secret".to_string())],
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response.body,
+            "{\"url\":\"http://localhost/v1/trades?key=secret\"}"
+        );
+        assert_eq!(
+            Cassette::load(&path).unwrap().interactions,
+            vec![Interaction {
+                url: "http://localhost/v1/trades?key=[REDACTED]".to_string(),
+                status: 429,
+                retry_after: Some(30),
+                body: "{\"url\":\"http://localhost/v1/trades?key=[REDACTED]\"}".to_string(),
+            }]
+        );
+        fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_replay_interactions_in_order() {
+        let path = tmp_path();
+        Cassette {
+            interactions: vec![
+                interaction("http://localhost:1234/v1/trades?page=0", "first"),
+                interaction("http://localhost:1234/v1/wallets", "wallets"),
+                interaction("http://localhost:1234/v1/trades?page=0", "second"),
+            ],
+        }
+        .save(&path)
+        .unwrap();
+        let transport = ReplayTransport::new(&path);
+        let request = HttpRequest {
+            url: "https://api.bitpanda.com/v1/trades?page=0".to_string(),
+            headers: vec![],
+        };
+        assert_eq!(transport.send(request.clone()).await.unwrap().body, "first");
+        assert_eq!(
+            transport.send(request.clone()).await.unwrap().body,
+            "second"
+        );
+        assert!(matches!(
+            transport.send(request).await.unwrap_err(),
+            ApiError::Cassette(_)
+        ));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_fail_replaying_missing_cassette() {
+        let transport = ReplayTransport::new(tmp_path());
+        assert!(matches!(
+            transport
+                .send(HttpRequest {
+                    url: "https://api.bitpanda.com/v1/trades".to_string(),
+                    headers: vec![],
+                })
+                .await
+                .unwrap_err(),
+            ApiError::Cassette(_)
+        ));
+    }
+
+    fn interaction(url: &str, body: &str) -> Interaction {
+        Interaction {
+            url: url.to_string(),
+            status: 200,
+            retry_after: None,
+            body: body.to_string(),
+        }
+    }
+
+    fn tmp_path() -> PathBuf {
+        std::env::temp_dir().join(format!("bitpanda-cassette-{}.json", fastrand::u64(..)))
+    }
+}
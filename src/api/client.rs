@@ -1,46 +1,101 @@
 //! # Bitpanda API client
 
-use async_recursion::async_recursion;
-
-use super::{ApiError, ApiResult};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures_util::future;
+use futures_util::stream::{self, Stream, StreamExt, TryStreamExt};
+use reqwest::header::RETRY_AFTER;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+
+use super::rate_limit::RateLimiter;
+use super::transport::{HttpRequest, Transport};
+use super::{ApiError, ApiResult, ClientBuilder, RetryPolicy, TradeFilter, TransactionFilter};
 use crate::model::crypto_wallet::CryptoWalletTransaction;
 use crate::model::fiat_wallet::FiatWalletTransaction;
 use crate::model::ohlc::Period;
 use crate::model::{
-    Asset, AssetClass, AssetWallet, CryptoWallet, FiatWallet, OpenHighLowCloseChart, Trade,
+    Asset, AssetClass, AssetWallet, CryptoWallet, FiatWallet, OpenHighLowCloseChart, Ticker, Trade,
     TransactionStatus, TransactionType,
 };
 
 mod asset_wallet_response;
 mod crypto_wallet_response;
 mod crypto_wallet_tx_response;
+mod error_response;
 mod fiat_wallet_response;
 mod fiat_wallet_tx_response;
 mod get_assets_response;
 mod get_ohlc_response;
+mod raw;
+mod ticker_response;
 mod trade_response;
 
 use asset_wallet_response::AssetWalletResponse;
 use crypto_wallet_response::CryptoWalletResponse;
-use crypto_wallet_tx_response::CryptoWalletTxResponse;
+use crypto_wallet_tx_response::{CryptoWalletTxResponse, SingleCryptoWalletTxResponse};
+use error_response::ErrorResponse;
 use fiat_wallet_response::FiatWalletResponse;
-use fiat_wallet_tx_response::FiatWalletTxResponse;
+use fiat_wallet_tx_response::{FiatWalletTxResponse, SingleFiatWalletTxResponse};
 use get_assets_response::GetAssetsResponse;
 use get_ohlc_response::GetOhlcResponse;
-use trade_response::TradeResponse;
+pub use raw::RawClient;
+use ticker_response::TickerResponse;
+use trade_response::{SingleTradeResponse, TradeResponse};
 
-const BITPANDA_API_URL: &str = "https://api.bitpanda.com/v1";
-const BITPANDA_PUBLIC_URL: &str = "https://api.bitpanda.com";
 const TRADE_DEFAULT_PAGE_SIZE: usize = 25;
 const ASSETS_DEFAULT_PAGE_SIZE: usize = 500;
 
-/// Bitpanda api client
-#[derive(Default)]
+/// Bitpanda api client.
+///
+/// The client holds a pooled http transport, so it should be created once and reused.
+/// Cloning the client is cheap and clones share the same transport
+#[derive(Clone)]
 pub struct Client {
-    x_apikey: Option<String>,
+    pub(super) x_apikey: Option<String>,
+    pub(super) api_url: String,
+    pub(super) public_url: String,
+    pub(super) transport: Arc<dyn Transport>,
+    pub(super) retry_policy: RetryPolicy,
+    pub(super) private_rate_limiter: Option<RateLimiter>,
+    pub(super) public_rate_limiter: Option<RateLimiter>,
+}
+
+/// Bitpanda host a request is sent to
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Host {
+    /// Private v1 API
+    Private,
+    /// Public v3 API and the unauthenticated endpoints of the v1 API
+    Public,
+}
+
+/// A request to a Bitpanda host
+struct Request {
+    host: Host,
+    http: HttpRequest,
+}
+
+/// Response of a paginated endpoint
+trait Paginated: DeserializeOwned {
+    /// Get the next page to fetch, if any
+    fn next_page(&self) -> Option<usize>;
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        ClientBuilder::default().build()
+    }
 }
 
 impl Client {
+    /// Get a builder to configure the client
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
     /// Construct client with x-apikey
     pub fn x_apikey(mut self, apikey: impl ToString) -> Self {
         self.x_apikey = Some(apikey.to_string());
@@ -53,12 +108,8 @@ impl Client {
     /// Get asset wallets for user.
     /// Requires APIKEY
     pub async fn get_asset_wallets(&self) -> ApiResult<Vec<AssetWallet>> {
-        let response: AssetWalletResponse = self
-            .request_with_auth("asset-wallets")?
-            .send()
-            .await?
-            .json()
-            .await?;
+        let response: AssetWalletResponse =
+            self.send(self.request_with_auth("asset-wallets")?).await?;
 
         Ok(response.into_asset_wallets())
     }
@@ -66,12 +117,7 @@ impl Client {
     /// Get crypto wallets
     /// Requires APIKEY
     pub async fn get_crypto_wallets(&self) -> ApiResult<Vec<CryptoWallet>> {
-        let response: CryptoWalletResponse = self
-            .request_with_auth("wallets")?
-            .send()
-            .await?
-            .json()
-            .await?;
+        let response: CryptoWalletResponse = self.send(self.request_with_auth("wallets")?).await?;
 
         Ok(response.into_crypto_wallets())
     }
@@ -79,12 +125,8 @@ impl Client {
     /// Get FIAT wallets
     /// Requires APIKEY
     pub async fn get_fiat_wallets(&self) -> ApiResult<Vec<FiatWallet>> {
-        let response: FiatWalletResponse = self
-            .request_with_auth("fiatwallets")?
-            .send()
-            .await?
-            .json()
-            .await?;
+        let response: FiatWalletResponse =
+            self.send(self.request_with_auth("fiatwallets")?).await?;
 
         Ok(response.into_fiat_wallets())
     }
@@ -99,7 +141,53 @@ impl Client {
     /// If max_results is specified, only the amount of trades specified are fetched
     /// Requires APIKEY
     pub async fn get_trades_ex(&self, max_results: Option<usize>) -> ApiResult<Vec<Trade>> {
-        self.do_get_trades(vec![], 0, max_results).await
+        collect(
+            self.paginate_trades(
+                &TradeFilter::default(),
+                page_size(max_results),
+                TradeResponse::into_trades,
+            ),
+            max_results,
+        )
+        .await
+    }
+
+    /// get user's trade with the provided id.
+    /// Returns `ApiError::NotFound` if there is no such trade
+    /// Requires APIKEY
+    pub async fn get_trade(&self, id: &str) -> ApiResult<Trade> {
+        let response: SingleTradeResponse = self
+            .send(self.request_with_auth(format!("trades/{id}"))?)
+            .await?;
+
+        response.into_trade()
+    }
+
+    /// get user's trades matching `filter`.
+    /// Requires APIKEY
+    pub async fn get_trades_with_filter(&self, filter: TradeFilter) -> ApiResult<Vec<Trade>> {
+        self.trades_stream_with_filter(filter).try_collect().await
+    }
+
+    /// Stream user's trades, from the most recent one.
+    /// Pages are fetched lazily while the stream is polled, so dropping the stream stops the pagination.
+    /// Requires APIKEY
+    pub fn trades_stream(&self) -> impl Stream<Item = ApiResult<Trade>> + '_ {
+        self.trades_stream_with_filter(TradeFilter::default())
+    }
+
+    /// Stream user's trades matching `filter`, from the most recent one.
+    /// Pages are fetched lazily while the stream is polled, so dropping the stream stops the pagination.
+    /// Requires APIKEY
+    pub fn trades_stream_with_filter(
+        &self,
+        filter: TradeFilter,
+    ) -> impl Stream<Item = ApiResult<Trade>> + '_ {
+        filter.apply(self.paginate_trades(
+            &filter,
+            TRADE_DEFAULT_PAGE_SIZE,
+            TradeResponse::into_trades,
+        ))
     }
 
     /// Get crypto wallet transactions
@@ -118,10 +206,61 @@ impl Client {
         status: Option<TransactionStatus>,
         max_results: Option<usize>,
     ) -> ApiResult<Vec<CryptoWalletTransaction>> {
-        self.do_get_crypto_wallet_transactions(vec![], transaction_type, status, 0, max_results)
+        let filter = TransactionFilter {
+            transaction_type,
+            status,
+            ..Default::default()
+        };
+        collect(
+            self.paginate_crypto_wallet_transactions(
+                &filter,
+                page_size(max_results),
+                CryptoWalletTxResponse::into_transactions,
+            ),
+            max_results,
+        )
+        .await
+    }
+
+    /// Get crypto wallet transaction with the provided id.
+    /// Returns `ApiError::NotFound` if there is no such transaction
+    /// Requires APIKEY
+    pub async fn get_crypto_wallet_transaction(
+        &self,
+        id: &str,
+    ) -> ApiResult<CryptoWalletTransaction> {
+        let response: SingleCryptoWalletTxResponse = self
+            .send(self.request_with_auth(format!("wallets/transactions/{id}"))?)
+            .await?;
+
+        response.into_transaction()
+    }
+
+    /// Get crypto wallet transactions matching `filter`
+    /// Requires APIKEY
+    pub async fn get_crypto_wallet_transactions_with_filter(
+        &self,
+        filter: TransactionFilter,
+    ) -> ApiResult<Vec<CryptoWalletTransaction>> {
+        self.crypto_wallet_transactions_stream(filter)
+            .try_collect()
             .await
     }
 
+    /// Stream crypto wallet transactions matching `filter`, from the most recent one.
+    /// Pages are fetched lazily while the stream is polled, so dropping the stream stops the pagination.
+    /// Requires APIKEY
+    pub fn crypto_wallet_transactions_stream(
+        &self,
+        filter: TransactionFilter,
+    ) -> impl Stream<Item = ApiResult<CryptoWalletTransaction>> + '_ {
+        filter.apply(self.paginate_crypto_wallet_transactions(
+            &filter,
+            TRADE_DEFAULT_PAGE_SIZE,
+            CryptoWalletTxResponse::into_transactions,
+        ))
+    }
+
     /// Get fiat wallet transactions
     /// Requires APIKEY
     pub async fn get_fiat_wallet_transactions(&self) -> ApiResult<Vec<FiatWalletTransaction>> {
@@ -137,13 +276,65 @@ impl Client {
         status: Option<TransactionStatus>,
         max_results: Option<usize>,
     ) -> ApiResult<Vec<FiatWalletTransaction>> {
-        self.do_get_fiat_wallet_transactions(vec![], transaction_type, status, 0, max_results)
+        let filter = TransactionFilter {
+            transaction_type,
+            status,
+            ..Default::default()
+        };
+        collect(
+            self.paginate_fiat_wallet_transactions(
+                &filter,
+                page_size(max_results),
+                FiatWalletTxResponse::into_transactions,
+            ),
+            max_results,
+        )
+        .await
+    }
+
+    /// Get fiat wallet transaction with the provided id.
+    /// Returns `ApiError::NotFound` if there is no such transaction
+    /// Requires APIKEY
+    pub async fn get_fiat_wallet_transaction(&self, id: &str) -> ApiResult<FiatWalletTransaction> {
+        let response: SingleFiatWalletTxResponse = self
+            .send(self.request_with_auth(format!("fiatwallets/transactions/{id}"))?)
+            .await?;
+
+        response.into_transaction()
+    }
+
+    /// Get fiat wallet transactions matching `filter`
+    /// Requires APIKEY
+    pub async fn get_fiat_wallet_transactions_with_filter(
+        &self,
+        filter: TransactionFilter,
+    ) -> ApiResult<Vec<FiatWalletTransaction>> {
+        self.fiat_wallet_transactions_stream(filter)
+            .try_collect()
             .await
     }
 
+    /// Stream fiat wallet transactions matching `filter`, from the most recent one.
+    /// Pages are fetched lazily while the stream is polled, so dropping the stream stops the pagination.
+    /// Requires APIKEY
+    pub fn fiat_wallet_transactions_stream(
+        &self,
+        filter: TransactionFilter,
+    ) -> impl Stream<Item = ApiResult<FiatWalletTransaction>> + '_ {
+        filter.apply(self.paginate_fiat_wallet_transactions(
+            &filter,
+            TRADE_DEFAULT_PAGE_SIZE,
+            FiatWalletTxResponse::into_transactions,
+        ))
+    }
+
     /// Get assets available on Bitpanda by class
     pub async fn get_assets(&self, asset_class: AssetClass) -> ApiResult<Vec<Asset>> {
-        self.do_get_assets(vec![], asset_class, 0).await
+        self.paginate_assets(asset_class, move |response: GetAssetsResponse| {
+            Ok(response.into_assets(asset_class))
+        })
+        .try_collect()
+        .await
     }
 
     /// get OHLC for provided symbols
@@ -153,235 +344,370 @@ impl Client {
         pid: &str,
         currency: &str,
     ) -> ApiResult<OpenHighLowCloseChart> {
-        let url = format!("ohlc/{pid}/{currency}/{}", period.to_string());
+        let url = format!("ohlc/{pid}/{currency}/{period}");
 
         Ok(self
-            .pub_request_v3(url)
-            .send()
-            .await?
-            .json::<GetOhlcResponse>()
+            .send::<GetOhlcResponse>(self.pub_request_v3(url))
             .await?
             .into_ohlc(period))
     }
 
-    #[async_recursion]
-    async fn do_get_trades(
-        &self,
-        mut trades: Vec<Trade>,
-        page: usize,
-        max_results: Option<usize>,
-    ) -> ApiResult<Vec<Trade>> {
-        let page_size = match max_results {
-            Some(sz) if trades.len() + TRADE_DEFAULT_PAGE_SIZE > sz => {
-                sz.checked_sub(trades.len()).unwrap_or_default()
-            }
-            Some(_) | None => TRADE_DEFAULT_PAGE_SIZE,
-        };
-        let url = format!("trades?page={page}&page_size={page_size}");
-        trace!("next get trade url: {url}");
+    /// Get the current prices of all the assets in all the supported fiat currencies
+    pub async fn get_ticker(&self) -> ApiResult<Ticker> {
+        let response: TickerResponse = self.send(self.pub_request_v1("ticker")).await?;
 
-        let response: TradeResponse = self.request_with_auth(url)?.send().await?.json().await?;
+        Ok(response.into_ticker())
+    }
 
-        let next_page = response.next_page();
+    fn paginate_assets<'a, P, T>(
+        &'a self,
+        asset_class: AssetClass,
+        convert: impl FnMut(P) -> ApiResult<Vec<T>> + 'a,
+    ) -> impl Stream<Item = ApiResult<T>> + 'a
+    where
+        P: Paginated + 'a,
+        T: 'a,
+    {
+        self.paginate(
+            move |page| {
+                let url = format!(
+                    "assets?page={page}&page_size={ASSETS_DEFAULT_PAGE_SIZE}&type[]={asset_class}"
+                );
+                trace!("next get assets url: {url}");
+                Ok(self.pub_request_v3(url))
+            },
+            convert,
+        )
+    }
 
-        trades.extend(response.into_trades()?);
+    fn paginate_trades<'a, P, T>(
+        &'a self,
+        filter: &TradeFilter,
+        page_size: usize,
+        convert: impl FnMut(P) -> ApiResult<Vec<T>> + 'a,
+    ) -> impl Stream<Item = ApiResult<T>> + 'a
+    where
+        P: Paginated + 'a,
+        T: 'a,
+    {
+        let query_args = filter.query_args();
+        self.paginate(
+            move |page| {
+                let url = format!("trades?page={page}&page_size={page_size}{query_args}");
+                trace!("next get trade url: {url}");
+                self.request_with_auth(url)
+            },
+            convert,
+        )
+    }
 
-        if let Some(max_results) = max_results {
-            if trades.len() >= max_results {
-                return Ok(trades);
-            }
-        }
+    fn paginate_crypto_wallet_transactions<'a, P, T>(
+        &'a self,
+        filter: &TransactionFilter,
+        page_size: usize,
+        convert: impl FnMut(P) -> ApiResult<Vec<T>> + 'a,
+    ) -> impl Stream<Item = ApiResult<T>> + 'a
+    where
+        P: Paginated + 'a,
+        T: 'a,
+    {
+        let query_args = filter.query_args();
+        self.paginate(
+            move |page| {
+                let url =
+                    format!("wallets/transactions?page={page}&page_size={page_size}{query_args}");
+                trace!("next get crypto transactions url: {url}");
+                self.request_with_auth(url)
+            },
+            convert,
+        )
+    }
 
-        if let Some(page) = next_page {
-            trace!("there are still trades to be fetched");
-            self.do_get_trades(trades, page, max_results).await
-        } else {
-            Ok(trades)
-        }
+    fn paginate_fiat_wallet_transactions<'a, P, T>(
+        &'a self,
+        filter: &TransactionFilter,
+        page_size: usize,
+        convert: impl FnMut(P) -> ApiResult<Vec<T>> + 'a,
+    ) -> impl Stream<Item = ApiResult<T>> + 'a
+    where
+        P: Paginated + 'a,
+        T: 'a,
+    {
+        let query_args = filter.query_args();
+        self.paginate(
+            move |page| {
+                let url = format!(
+                    "fiatwallets/transactions?page={page}&page_size={page_size}{query_args}"
+                );
+                trace!("next get fiat transactions url: {url}");
+                self.request_with_auth(url)
+            },
+            convert,
+        )
     }
 
-    #[async_recursion]
-    async fn do_get_crypto_wallet_transactions(
-        &self,
-        mut txs: Vec<CryptoWalletTransaction>,
-        transaction_type: Option<TransactionType>,
-        status: Option<TransactionStatus>,
-        page: usize,
-        max_results: Option<usize>,
-    ) -> ApiResult<Vec<CryptoWalletTransaction>> {
-        let page_size = match max_results {
-            Some(sz) if txs.len() + TRADE_DEFAULT_PAGE_SIZE > sz => {
-                sz.checked_sub(txs.len()).unwrap_or_default()
+    /// Stream the items of a paginated endpoint.
+    /// `request` builds the request for the provided page, while `convert` converts the response into items
+    fn paginate<'a, P, T, R, C>(
+        &'a self,
+        request: R,
+        mut convert: C,
+    ) -> impl Stream<Item = ApiResult<T>> + 'a
+    where
+        P: Paginated + 'a,
+        T: 'a,
+        R: Fn(usize) -> ApiResult<Request> + 'a,
+        C: FnMut(P) -> ApiResult<Vec<T>> + 'a,
+    {
+        stream::try_unfold(Some(0), move |page| {
+            let request = page.map(&request).transpose();
+            async move {
+                let Some(request) = request? else {
+                    return Ok(None);
+                };
+                let response: P = self.send(request).await?;
+                let next_page = response.next_page();
+                if next_page.is_some() {
+                    trace!("there are still pages to be fetched");
+                }
+
+                Ok(Some((response, next_page)))
             }
-            Some(_) | None => TRADE_DEFAULT_PAGE_SIZE,
-        };
-
-        let transaction_type_arg = transaction_type
-            .map(|t| format!("&type={}", t.to_string()))
-            .unwrap_or_default();
-
-        let status_arg = status
-            .map(|s| format!("&status={}", s.to_string()))
-            .unwrap_or_default();
-
-        let url = format!("wallets/transactions?page={page}&page_size={page_size}{transaction_type_arg}{status_arg}");
-        trace!("next get crypto transactions url: {url}");
-
-        let response: CryptoWalletTxResponse =
-            self.request_with_auth(url)?.send().await?.json().await?;
-
-        let next_page = response.next_page();
-
-        txs.extend(response.into_transactions()?);
+        })
+        .and_then(move |response| future::ready(convert(response)))
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+    }
 
-        if let Some(max_results) = max_results {
-            if txs.len() >= max_results {
-                return Ok(txs);
+    /// Send request and deserialize the response body, retrying on transient failures
+    /// according to the client retry policy
+    async fn send<T>(&self, request: Request) -> ApiResult<T>
+    where
+        T: DeserializeOwned,
+    {
+        let rate_limiter = match request.host {
+            Host::Private => self.private_rate_limiter.as_ref(),
+            Host::Public => self.public_rate_limiter.as_ref(),
+        };
+        let mut attempt = 1;
+        loop {
+            if let Some(rate_limiter) = rate_limiter {
+                rate_limiter.acquire().await;
+            }
+            match self.send_once(request.http.clone()).await {
+                Err(err) if self.retry_policy.should_retry(attempt, &err) => {
+                    let delay = self.retry_policy.delay(attempt, &err);
+                    debug!(
+                        "attempt {attempt}/{} failed: {err}; retrying in {delay:?}",
+                        self.retry_policy.max_attempts()
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
             }
         }
+    }
 
-        if let Some(page) = next_page {
-            trace!("there are still tx to be fetched");
-            self.do_get_crypto_wallet_transactions(txs, transaction_type, status, page, max_results)
-                .await
+    /// Send request and deserialize the response body.
+    /// If the server responds with an error status, the error payload is converted into an `ApiError`
+    async fn send_once<T>(&self, request: HttpRequest) -> ApiResult<T>
+    where
+        T: DeserializeOwned,
+    {
+        let response = self.transport.send(request).await?;
+        let status = StatusCode::from_u16(response.status)
+            .map_err(|_| ApiError::UnexpectedValue(response.status.to_string()))?;
+        if status.is_success() {
+            Ok(serde_json::from_str(&response.body)?)
         } else {
-            Ok(txs)
+            let retry_after = response.header(RETRY_AFTER.as_str()).and_then(retry_after);
+            Err(ErrorResponse::into_error(
+                status,
+                retry_after,
+                &response.body,
+            ))
         }
     }
 
-    #[async_recursion]
-    async fn do_get_fiat_wallet_transactions(
-        &self,
-        mut txs: Vec<FiatWalletTransaction>,
-        transaction_type: Option<TransactionType>,
-        status: Option<TransactionStatus>,
-        page: usize,
-        max_results: Option<usize>,
-    ) -> ApiResult<Vec<FiatWalletTransaction>> {
-        let page_size = match max_results {
-            Some(sz) if txs.len() + TRADE_DEFAULT_PAGE_SIZE > sz => {
-                sz.checked_sub(txs.len()).unwrap_or_default()
-            }
-            Some(_) | None => TRADE_DEFAULT_PAGE_SIZE,
-        };
-
-        let transaction_type_arg = transaction_type
-            .map(|t| format!("&type={}", t.to_string()))
-            .unwrap_or_default();
-
-        let status_arg = status
-            .map(|s| format!("&status={}", s.to_string()))
-            .unwrap_or_default();
-
-        let url = format!("fiatwallets/transactions?page={page}&page_size={page_size}{transaction_type_arg}{status_arg}");
-        trace!("next get crypto transactions url: {url}");
-
-        let response: FiatWalletTxResponse =
-            self.request_with_auth(url)?.send().await?.json().await?;
-        let next_page = response.next_page();
-
-        txs.extend(response.into_transactions()?);
-
-        if let Some(max_results) = max_results {
-            if txs.len() >= max_results {
-                return Ok(txs);
-            }
+    fn priv_request(&self, url: impl ToString) -> Request {
+        Request {
+            host: Host::Private,
+            http: HttpRequest::get(format!("{}/{}", self.api_url, url.to_string())),
         }
+    }
 
-        if let Some(page) = next_page {
-            trace!("there are still tx to be fetched");
-            self.do_get_fiat_wallet_transactions(txs, transaction_type, status, page, max_results)
-                .await
-        } else {
-            Ok(txs)
+    /// Request to an unauthenticated endpoint of the v1 API, which is charged to the public rate limit
+    fn pub_request_v1(&self, url: impl ToString) -> Request {
+        Request {
+            host: Host::Public,
+            http: HttpRequest::get(format!("{}/{}", self.api_url, url.to_string())),
         }
     }
 
-    #[async_recursion]
-    async fn do_get_assets(
-        &self,
-        mut assets: Vec<Asset>,
-        asset_class: AssetClass,
-        page: usize,
-    ) -> ApiResult<Vec<Asset>> {
-        let url = format!(
-            "assets?page={page}&page_size={ASSETS_DEFAULT_PAGE_SIZE}&type[]={}",
-            asset_class.to_string()
-        );
-        trace!("next get assets url: {url}");
-
-        let response: GetAssetsResponse = self.pub_request_v3(url).send().await?.json().await?;
-
-        let next_page = response.next_page();
+    fn pub_request_v3(&self, url: impl ToString) -> Request {
+        Request {
+            host: Host::Public,
+            http: HttpRequest::get(format!("{}/{}", self.public_url, url.to_string())),
+        }
+    }
 
-        assets.extend(response.into_assets(asset_class));
+    fn request_with_auth(&self, url: impl ToString) -> ApiResult<Request> {
+        if let Some(apikey) = &self.x_apikey {
+            let mut request = self.priv_request(url);
+            request
+                .http
+                .headers
+                .push(("X-API-KEY".to_string(), apikey.clone()));
 
-        if let Some(page) = next_page {
-            trace!("there are still assets to be fetched");
-            self.do_get_assets(assets, asset_class, page).await
+            Ok(request)
         } else {
-            Ok(assets)
+            Err(ApiError::Unauthorized("missing x-apikey".to_string()))
         }
     }
+}
 
-    fn priv_request(&self, url: impl ToString) -> reqwest::RequestBuilder {
-        reqwest::Client::new().get(format!("{BITPANDA_API_URL}/{}", url.to_string()))
+/// Parse a `Retry-After` header, either in seconds or as an HTTP date
+fn retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
     }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    // a date in the past means the request can be retried right away
+    Some(
+        (date.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
 
-    fn pub_request_v3(&self, url: impl ToString) -> reqwest::RequestBuilder {
-        reqwest::Client::new().get(format!("{BITPANDA_PUBLIC_URL}/v3/{}", url.to_string()))
-    }
+/// Get the page size to use to fetch at most `max_results` items
+fn page_size(max_results: Option<usize>) -> usize {
+    max_results
+        .unwrap_or(TRADE_DEFAULT_PAGE_SIZE)
+        .clamp(1, TRADE_DEFAULT_PAGE_SIZE)
+}
 
-    fn request_with_auth(&self, url: impl ToString) -> ApiResult<reqwest::RequestBuilder> {
-        if let Some(apikey) = &self.x_apikey {
-            Ok(self.priv_request(url).header("X-API-KEY", apikey))
-        } else {
-            Err(ApiError::Unauthorized)
-        }
-    }
+/// Collect at most `max_results` items from `stream`
+async fn collect<T>(
+    stream: impl Stream<Item = ApiResult<T>>,
+    max_results: Option<usize>,
+) -> ApiResult<Vec<T>> {
+    stream
+        .take(max_results.unwrap_or(usize::MAX))
+        .try_collect()
+        .await
 }
 
 #[cfg(test)]
 mod test {
 
     use super::*;
+    use crate::api::Cassette;
+    use crate::model::ohlc::Ohlc;
+    use crate::model::TradeType;
+    use crate::testing::{fixture, MockServer, MockTransport};
 
     use pretty_assertions::assert_eq;
+    use rust_decimal::Decimal;
+    use std::collections::HashMap;
 
     #[tokio::test]
     async fn should_get_asset_wallets() {
-        assert!(client().get_asset_wallets().await.is_ok());
+        let server = server().await;
+        let wallets = server.client().get_asset_wallets().await.unwrap();
+        assert_eq!(wallets.len(), 6);
+        assert!(
+            wallets
+                .iter()
+                .any(|wallet| wallet.class == AssetClass::Cryptoindex
+                    && wallet.asset_symbol == "BCI5")
+        );
     }
 
     #[tokio::test]
     async fn should_get_crypto_wallets() {
-        assert!(client().get_crypto_wallets().await.is_ok());
+        let server = server().await;
+        let wallets = server.client().get_crypto_wallets().await.unwrap();
+        assert_eq!(wallets.len(), 1);
+        assert_eq!(wallets[0].symbol, "BTC");
     }
 
     #[tokio::test]
     async fn should_get_fiat_wallets() {
-        assert!(client().get_fiat_wallets().await.is_ok());
+        let server = server().await;
+        let wallets = server.client().get_fiat_wallets().await.unwrap();
+        assert_eq!(wallets.len(), 1);
+        assert_eq!(wallets[0].symbol, "EUR");
     }
 
     #[tokio::test]
     async fn should_get_all_trades() {
-        assert!(client().get_trades().await.is_ok());
+        let server = server().await;
+        let trades = server.client().get_trades().await.unwrap();
+        assert_eq!(trades, fixture::trades(150));
     }
 
     #[tokio::test]
     async fn should_get_limited_trades() {
-        assert_eq!(client().get_trades_ex(Some(128)).await.unwrap().len(), 128);
+        let server = server().await;
+        assert_eq!(
+            server
+                .client()
+                .get_trades_ex(Some(128))
+                .await
+                .unwrap()
+                .len(),
+            128
+        );
+    }
+
+    #[tokio::test]
+    async fn should_get_trade() {
+        let server = server().await;
+        let client = server.client();
+        assert_eq!(
+            client.get_trade("trade-42").await.unwrap(),
+            fixture::trades(150).remove(42)
+        );
+        assert!(matches!(
+            client.get_trade("trade-999").await.unwrap_err(),
+            ApiError::NotFound(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_get_trades_by_type() {
+        let server = server().await;
+        let trades = server
+            .client()
+            .get_trades_with_filter(TradeFilter::default().trade_type(TradeType::Sell))
+            .await
+            .unwrap();
+        assert_eq!(trades.len(), 75);
+        assert!(trades.iter().all(|t| t.r#type == TradeType::Sell));
     }
 
     #[tokio::test]
     async fn should_get_crypto_transactions() {
-        assert!(client().get_crypto_wallet_transactions().await.is_ok());
+        let server = server().await;
+        assert_eq!(
+            server
+                .client()
+                .get_crypto_wallet_transactions()
+                .await
+                .unwrap(),
+            fixture::crypto_wallet_transactions(60)
+        );
     }
 
     #[tokio::test]
     async fn should_get_crypto_transactions_limited() {
+        let server = server().await;
         assert_eq!(
-            client()
+            server
+                .client()
                 .get_crypto_wallet_transactions_ex(None, None, Some(45))
                 .await
                 .unwrap()
@@ -392,28 +718,38 @@ mod test {
 
     #[tokio::test]
     async fn should_get_crypto_transactions_by_type() {
-        assert!(client()
+        let server = server().await;
+        let transactions = server
+            .client()
             .get_crypto_wallet_transactions_ex(Some(TransactionType::Buy), None, Some(25))
             .await
-            .unwrap()
+            .unwrap();
+        assert_eq!(transactions.len(), 12);
+        assert!(transactions
             .iter()
             .all(|t| t.transaction_type == TransactionType::Buy));
     }
 
     #[tokio::test]
     async fn should_get_crypto_transactions_by_status() {
-        assert!(client()
+        let server = server().await;
+        let transactions = server
+            .client()
             .get_crypto_wallet_transactions_ex(None, Some(TransactionStatus::Canceled), Some(25))
             .await
-            .unwrap()
+            .unwrap();
+        assert_eq!(transactions.len(), 15);
+        assert!(transactions
             .iter()
             .all(|t| t.status == TransactionStatus::Canceled));
     }
 
     #[tokio::test]
     async fn should_get_fiat_transactions_limited() {
+        let server = server().await;
         assert_eq!(
-            client()
+            server
+                .client()
                 .get_fiat_wallet_transactions_ex(None, None, Some(45))
                 .await
                 .unwrap()
@@ -424,35 +760,47 @@ mod test {
 
     #[tokio::test]
     async fn should_get_fiat_transactions_by_type() {
-        assert!(client()
+        let server = server().await;
+        let transactions = server
+            .client()
             .get_fiat_wallet_transactions_ex(Some(TransactionType::Buy), None, Some(25))
             .await
-            .unwrap()
+            .unwrap();
+        assert_eq!(transactions.len(), 12);
+        assert!(transactions
             .iter()
             .all(|t| t.transaction_type == TransactionType::Buy));
     }
 
     #[tokio::test]
     async fn should_get_fiat_transactions_by_status() {
-        assert!(client()
+        let server = server().await;
+        let transactions = server
+            .client()
             .get_fiat_wallet_transactions_ex(None, Some(TransactionStatus::Canceled), Some(25))
             .await
-            .unwrap()
+            .unwrap();
+        assert_eq!(transactions.len(), 15);
+        assert!(transactions
             .iter()
             .all(|t| t.status == TransactionStatus::Canceled));
     }
 
     #[tokio::test]
     async fn should_get_assets() {
-        assert!(client()
+        let server = server().await;
+        let assets = server
+            .client()
             .get_assets(AssetClass::Cryptocurrency)
             .await
-            .is_ok());
+            .unwrap();
+        assert_eq!(assets, vec![btc()]);
     }
 
     #[tokio::test]
     async fn should_get_ohlc_for_btc() {
-        let client = client();
+        let server = server().await;
+        let client = server.client();
 
         let btc = client
             .get_assets(AssetClass::Cryptocurrency)
@@ -462,28 +810,325 @@ mod test {
             .find(|asset| asset.symbol == "BTC")
             .unwrap();
 
-        assert!(client.get_ohlc(Period::Day, &btc.pid, "EUR").await.is_ok());
-        assert!(client.get_ohlc(Period::Week, &btc.pid, "EUR").await.is_ok());
-        assert!(client
-            .get_ohlc(Period::Month, &btc.pid, "EUR")
+        for period in [
+            Period::Day,
+            Period::Week,
+            Period::Month,
+            Period::Year,
+            Period::FiveYears,
+        ] {
+            let chart = client.get_ohlc(period, &btc.pid, "EUR").await.unwrap();
+            assert_eq!(chart.period, period);
+            assert_eq!(chart.last_close(), Some(Decimal::new(21000, 0)));
+        }
+        assert!(matches!(
+            client
+                .get_ohlc(Period::Day, &btc.pid, "USD")
+                .await
+                .unwrap_err(),
+            ApiError::NotFound(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_get_ticker() {
+        let server = server().await;
+        let ticker = server.client().get_ticker().await.unwrap();
+        assert_eq!(ticker.price("BTC", "EUR"), Some(Decimal::new(21000, 0)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn should_charge_ticker_to_public_rate_limit() {
+        let transport = MockTransport::new();
+        let client = transport
+            .client_builder()
+            .private_rate_limit(crate::RateLimit::per_minute(1))
+            .build();
+        let start = tokio::time::Instant::now();
+        for _ in 0..3 {
+            client.get_ticker().await.unwrap();
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        client.get_fiat_wallets().await.unwrap();
+        client.get_fiat_wallets().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_secs(59));
+    }
+
+    #[tokio::test]
+    async fn should_get_raw_attributes() {
+        let server = server().await;
+        let client = server.client();
+        let raw = client.with_raw();
+
+        let trades = raw
+            .get_trades(TradeFilter::default().trade_type(TradeType::Sell))
+            .await
+            .unwrap();
+        assert_eq!(trades.len(), 75);
+        for (trade, attributes) in &trades {
+            assert_eq!(attributes["best_price"], trade.price.to_string());
+            assert_eq!(attributes["is_savings"], false);
+        }
+        let (trade, attributes) = raw.get_trade(&trades[0].0.id).await.unwrap();
+        assert_eq!(trade, trades[0].0);
+        assert_eq!(attributes, trades[0].1);
+
+        let wallets = raw.get_asset_wallets().await.unwrap();
+        assert_eq!(wallets.len(), 6);
+        assert!(wallets
+            .iter()
+            .all(|(wallet, attributes)| attributes["name"] == wallet.name.as_str()));
+
+        let transactions = raw
+            .get_crypto_wallet_transactions(TransactionFilter::default())
             .await
-            .is_ok());
-        assert!(client.get_ohlc(Period::Year, &btc.pid, "EUR").await.is_ok());
-        assert!(client
-            .get_ohlc(Period::FiveYears, &btc.pid, "EUR")
+            .unwrap();
+        assert_eq!(
+            transactions
+                .into_iter()
+                .map(|(tx, _)| tx)
+                .collect::<Vec<_>>(),
+            fixture::crypto_wallet_transactions(60)
+        );
+    }
+
+    #[tokio::test]
+    async fn should_follow_pagination_links() {
+        let server = server().await;
+        server
+            .client()
+            .get_crypto_wallet_transactions_ex(None, Some(TransactionStatus::Finished), None)
             .await
-            .is_ok());
+            .unwrap();
+        assert_eq!(
+            server.requests(),
+            vec![
+                "/v1/wallets/transactions?page=0&page_size=25&status=finished",
+                "/v1/wallets/transactions?page=2&page_size=25&status=finished",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_get_single_wallet_transactions() {
+        let transport = MockTransport::new();
+        transport.set_crypto_wallet_transactions(fixture::crypto_wallet_transactions(10));
+        transport.set_fiat_wallet_transactions(fixture::fiat_wallet_transactions(10));
+        let client = transport.client();
+        assert_eq!(
+            client
+                .get_crypto_wallet_transaction("crypto-tx-3")
+                .await
+                .unwrap(),
+            fixture::crypto_wallet_transactions(10).remove(3)
+        );
+        assert_eq!(
+            client
+                .get_fiat_wallet_transaction("fiat-tx-7")
+                .await
+                .unwrap(),
+            fixture::fiat_wallet_transactions(10).remove(7)
+        );
+        assert!(matches!(
+            client
+                .get_crypto_wallet_transaction("crypto-tx-99")
+                .await
+                .unwrap_err(),
+            ApiError::NotFound(_)
+        ));
+        assert!(matches!(
+            client
+                .get_fiat_wallet_transaction("fiat-tx-99")
+                .await
+                .unwrap_err(),
+            ApiError::NotFound(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_paginate_through_transport() {
+        let transport = MockTransport::new();
+        transport.set_trades(fixture::trades(60));
+        let client = transport.client();
+        assert_eq!(client.get_trades().await.unwrap(), fixture::trades(60));
+        assert_eq!(client.get_trades_ex(Some(30)).await.unwrap().len(), 30);
+        assert_eq!(
+            transport.requests(),
+            vec![
+                "/v1/trades?page=0&page_size=25",
+                "/v1/trades?page=2&page_size=25",
+                "/v1/trades?page=3&page_size=25",
+                "/v1/trades?page=0&page_size=25",
+                "/v1/trades?page=2&page_size=25",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_retry_failed_requests() {
+        let server = server().await;
+        server.fail_next(503, None);
+        server.fail_next(429, Some(Duration::from_secs(0)));
+        let client = server
+            .client_builder()
+            .retry_policy(RetryPolicy::new(3).base_delay(Duration::from_millis(1)))
+            .build();
+        assert_eq!(client.get_fiat_wallets().await.unwrap().len(), 1);
+        assert_eq!(server.requests().len(), 3);
+
+        server.fail_next(429, Some(Duration::from_secs(30)));
+        assert!(matches!(
+            server.client().get_fiat_wallets().await.unwrap_err(),
+            ApiError::RateLimited {
+                retry_after: Some(retry_after),
+                ..
+            } if retry_after == Duration::from_secs(30)
+        ));
+    }
+
+    #[test]
+    fn should_parse_retry_after() {
+        assert_eq!(retry_after(" 120 "), Some(Duration::from_secs(120)));
+        assert_eq!(
+            retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        let in_a_minute = (Utc::now() + chrono::Duration::seconds(60)).to_rfc2822();
+        let delay = retry_after(&in_a_minute).unwrap();
+        assert!(delay > Duration::from_secs(55) && delay <= Duration::from_secs(60));
+        assert_eq!(retry_after("soon"), None);
     }
 
     #[tokio::test]
     async fn should_return_error_if_unauthorized() {
         let client = Client::default();
         assert!(client.get_asset_wallets().await.is_err());
+
+        let server = server().await;
+        let client = server.client_builder().x_apikey("wrong").build();
+        assert!(matches!(
+            client.get_asset_wallets().await.unwrap_err(),
+            ApiError::Unauthorized(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_record_and_replay_interactions() {
+        let path =
+            std::env::temp_dir().join(format!("bitpanda-cassette-{}.json", fastrand::u64(..)));
+        let server = server().await;
+        let client = server.client_builder().record(&path).build();
+        let trades = client.get_trades().await.unwrap();
+        let wallets = client.get_asset_wallets().await.unwrap();
+        assert!(client.get_trade("trade-999").await.is_err());
+        drop(server);
+
+        let cassette = std::fs::read_to_string(&path).unwrap();
+        assert!(!cassette.contains(crate::testing::MOCK_API_KEY));
+        assert_eq!(Cassette::load(&path).unwrap().interactions.len(), 8);
+
+        let client = Client::builder().x_apikey("secret").replay(&path).build();
+        assert_eq!(client.get_trades().await.unwrap(), trades);
+        assert_eq!(client.get_asset_wallets().await.unwrap(), wallets);
+        assert!(matches!(
+            client.get_trade("trade-999").await.unwrap_err(),
+            ApiError::NotFound(_)
+        ));
+        assert!(matches!(
+            client.get_fiat_wallets().await.unwrap_err(),
+            ApiError::Cassette(_)
+        ));
+        std::fs::remove_file(path).unwrap();
     }
 
-    fn client() -> Client {
+    async fn server() -> MockServer {
         log_init();
-        Client::default().x_apikey(env!("X_API_KEY"))
+        let server = MockServer::start().await;
+        server.set_asset_wallets(
+            [
+                (AssetClass::Cryptocurrency, "1", "BTC"),
+                (AssetClass::Cryptoindex, "55", "BCI5"),
+                (AssetClass::Metal, "28", "XAU"),
+                (AssetClass::Commodity, "130", "OIL"),
+                (AssetClass::Etf, "200", "SPY"),
+                (AssetClass::Stock, "300", "AAPL"),
+            ]
+            .into_iter()
+            .map(|(class, asset_id, symbol)| AssetWallet {
+                asset_id: asset_id.to_string(),
+                asset_symbol: symbol.to_string(),
+                balance: Decimal::ONE,
+                class,
+                deleted: false,
+                id: format!("wallet-{}", symbol.to_lowercase()),
+                is_default: true,
+                name: format!("{symbol} wallet"),
+            })
+            .collect(),
+        );
+        server.set_crypto_wallets(vec![CryptoWallet {
+            balance: Decimal::new(125, 4),
+            cryptocoin_id: "1".to_string(),
+            deleted: false,
+            id: "wallet-btc".to_string(),
+            is_default: true,
+            name: "BTC wallet".to_string(),
+            pending_transactions_count: 0,
+            symbol: "BTC".to_string(),
+        }]);
+        server.set_fiat_wallets(vec![FiatWallet {
+            balance: Decimal::new(100050, 2),
+            fiat_id: "1".to_string(),
+            id: "fiat-wallet-eur".to_string(),
+            name: "EUR wallet".to_string(),
+            pending_transactions_count: 0,
+            symbol: "EUR".to_string(),
+        }]);
+        server.set_trades(fixture::trades(150));
+        server.set_crypto_wallet_transactions(fixture::crypto_wallet_transactions(60));
+        server.set_fiat_wallet_transactions(fixture::fiat_wallet_transactions(60));
+        server.set_assets(vec![btc()]);
+        for period in [
+            Period::Day,
+            Period::Week,
+            Period::Month,
+            Period::Year,
+            Period::FiveYears,
+        ] {
+            server.set_ohlc(
+                &btc().pid,
+                "EUR",
+                OpenHighLowCloseChart {
+                    chart: vec![Ohlc {
+                        close: Decimal::new(21000, 0),
+                        high: Decimal::new(21500, 0),
+                        low: Decimal::new(20500, 0),
+                        open: Decimal::new(20800, 0),
+                        time: "2023-06-30T00:00:00+00:00".parse().unwrap(),
+                    }],
+                    period,
+                },
+            );
+        }
+        server.set_ticker(Ticker {
+            prices: HashMap::from([(
+                "BTC".to_string(),
+                HashMap::from([("EUR".to_string(), Decimal::new(21000, 0))]),
+            )]),
+        });
+
+        server
+    }
+
+    fn btc() -> Asset {
+        Asset {
+            id: "1".to_string(),
+            pid: "17fbc6be-3a2d-45b7-9a9e-8d4e2b0f3d71".to_string(),
+            name: "Bitcoin".to_string(),
+            symbol: "BTC".to_string(),
+            r#type: AssetClass::Cryptocurrency,
+        }
     }
 
     fn log_init() {
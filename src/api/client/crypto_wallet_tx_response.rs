@@ -4,7 +4,7 @@ use chrono::{DateTime, FixedOffset};
 use lazy_regex::regex;
 use rust_decimal::Decimal;
 
-use super::ApiResult;
+use super::{ApiResult, Paginated};
 use crate::{model::crypto_wallet::CryptoWalletTransaction, ApiError};
 
 #[derive(Deserialize)]
@@ -13,8 +13,8 @@ pub struct CryptoWalletTxResponse {
     links: Links,
 }
 
-impl CryptoWalletTxResponse {
-    pub fn next_page(&self) -> Option<usize> {
+impl Paginated for CryptoWalletTxResponse {
+    fn next_page(&self) -> Option<usize> {
         if let Some(next) = &self.links.next {
             let rgx = regex!(r"page=([0-9]+)");
             rgx.captures(next).and_then(|captures| {
@@ -26,7 +26,9 @@ impl CryptoWalletTxResponse {
             None
         }
     }
+}
 
+impl CryptoWalletTxResponse {
     pub fn into_transactions(self) -> ApiResult<Vec<CryptoWalletTransaction>> {
         let mut txs = Vec::with_capacity(self.data.len());
         for tx in self.data.into_iter() {
@@ -37,6 +39,18 @@ impl CryptoWalletTxResponse {
     }
 }
 
+/// Response of the single transaction endpoint
+#[derive(Deserialize)]
+pub struct SingleCryptoWalletTxResponse {
+    data: Data,
+}
+
+impl SingleCryptoWalletTxResponse {
+    pub fn into_transaction(self) -> ApiResult<CryptoWalletTransaction> {
+        CryptoWalletTransaction::try_from(self.data)
+    }
+}
+
 impl TryFrom<Data> for CryptoWalletTransaction {
     type Error = ApiError;
 
@@ -117,4 +131,48 @@ mod test {
 
         assert!(response.next_page().is_none());
     }
+
+    #[test]
+    fn should_parse_single_crypto_wallet_transaction() {
+        let response: SingleCryptoWalletTxResponse = serde_json::from_str(
+            r#"{
+                "data": {
+                    "type": "wallet_transaction",
+                    "attributes": {
+                        "amount": "0.05000000",
+                        "recipient": "0xabc",
+                        "time": {
+                            "date_iso8601": "2023-03-01T08:00:00+01:00",
+                            "unix": "1677654000"
+                        },
+                        "confirmations": 10,
+                        "in_or_out": "outgoing",
+                        "type": "withdrawal",
+                        "status": "finished",
+                        "amount_eur": "55.00",
+                        "wallet_id": "eth-wallet",
+                        "cryptocoin_id": "5",
+                        "fee": "0.00100000",
+                        "current_fiat_id": "1",
+                        "current_fiat_amount": "60.00"
+                    },
+                    "id": "crypto-tx-1"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let tx = response.into_transaction().unwrap();
+        assert_eq!(tx.id, "crypto-tx-1");
+        assert_eq!(tx.amount, Decimal::new(5, 2));
+        assert_eq!(tx.fee, Decimal::new(1, 3));
+        assert_eq!(tx.confirmations, 10);
+        assert_eq!(tx.in_or_out, crate::model::InOrOut::Outgoing);
+        assert_eq!(tx.status, crate::model::TransactionStatus::Finished);
+        assert_eq!(
+            tx.transaction_type,
+            crate::model::TransactionType::Withdrawal
+        );
+        assert_eq!(tx.wallet_id, "eth-wallet");
+    }
 }
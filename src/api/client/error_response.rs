@@ -0,0 +1,139 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+use crate::ApiError;
+
+/// Error payload returned by Bitpanda on failed requests
+#[derive(Deserialize)]
+pub struct ErrorResponse {
+    #[serde(default)]
+    errors: Vec<Error>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Error {
+    title: Option<String>,
+    detail: Option<String>,
+}
+
+impl ErrorResponse {
+    /// Get a human readable message out of the error payload.
+    /// If the body is not a Bitpanda error payload, the raw body is returned
+    pub fn message(body: &str) -> String {
+        match serde_json::from_str::<Self>(body) {
+            Ok(response) => {
+                let mut messages: Vec<String> = response
+                    .errors
+                    .into_iter()
+                    .filter_map(|error| match (error.title, error.detail) {
+                        (Some(title), Some(detail)) => Some(format!("{title}: {detail}")),
+                        (Some(msg), None) | (None, Some(msg)) => Some(msg),
+                        (None, None) => None,
+                    })
+                    .collect();
+                messages.extend(response.error);
+                if messages.is_empty() {
+                    body.to_string()
+                } else {
+                    messages.join("; ")
+                }
+            }
+            Err(_) => body.to_string(),
+        }
+    }
+
+    /// Convert a failed response into an `ApiError`
+    pub fn into_error(status: StatusCode, retry_after: Option<Duration>, body: &str) -> ApiError {
+        let message = Self::message(body);
+        debug!("request failed with status {status}: {message}");
+
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ApiError::Unauthorized(message),
+            StatusCode::NOT_FOUND => ApiError::NotFound(message),
+            StatusCode::TOO_MANY_REQUESTS => ApiError::RateLimited {
+                retry_after,
+                message,
+            },
+            status if status.is_server_error() => ApiError::ServerError {
+                status: status.as_u16(),
+                body: message,
+            },
+            status => ApiError::UnexpectedStatus {
+                status: status.as_u16(),
+                message,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_get_message_from_error_payload() {
+        assert_eq!(
+            ErrorResponse::message(
+                r#"{"errors":[{"status":404,"title":"Not found","detail":"Trade not found"}]}"#
+            ),
+            "Not found: Trade not found"
+        );
+        assert_eq!(
+            ErrorResponse::message(r#"{"error":"invalid page"}"#),
+            "invalid page"
+        );
+        assert_eq!(
+            ErrorResponse::message("<html>bad gateway</html>"),
+            "<html>bad gateway</html>"
+        );
+    }
+
+    #[test]
+    fn should_map_status_to_error() {
+        assert!(matches!(
+            ErrorResponse::into_error(
+                StatusCode::UNAUTHORIZED,
+                None,
+                r#"{"errors":[{"title":"Unauthorized","detail":"API key expired"}]}"#
+            ),
+            ApiError::Unauthorized(msg) if msg == "Unauthorized: API key expired"
+        ));
+        assert!(matches!(
+            ErrorResponse::into_error(
+                StatusCode::FORBIDDEN,
+                None,
+                r#"{"errors":[{"detail":"Missing scope trade"}]}"#
+            ),
+            ApiError::Unauthorized(msg) if msg == "Missing scope trade"
+        ));
+        assert!(matches!(
+            ErrorResponse::into_error(
+                StatusCode::NOT_FOUND,
+                None,
+                r#"{"errors":[{"detail":"Trade not found"}]}"#
+            ),
+            ApiError::NotFound(msg) if msg == "Trade not found"
+        ));
+        assert!(matches!(
+            ErrorResponse::into_error(
+                StatusCode::TOO_MANY_REQUESTS,
+                Some(Duration::from_secs(30)),
+                r#"{"errors":[{"detail":"Too many requests"}]}"#
+            ),
+            ApiError::RateLimited { retry_after: Some(d), message }
+                if d == Duration::from_secs(30) && message == "Too many requests"
+        ));
+        assert!(matches!(
+            ErrorResponse::into_error(StatusCode::BAD_GATEWAY, None, "oops"),
+            ApiError::ServerError { status: 502, body } if body == "oops"
+        ));
+        assert!(matches!(
+            ErrorResponse::into_error(StatusCode::BAD_REQUEST, None, "bad"),
+            ApiError::UnexpectedStatus { status: 400, message } if message == "bad"
+        ));
+    }
+}
@@ -4,7 +4,7 @@ use chrono::{DateTime, FixedOffset};
 use lazy_regex::regex;
 use rust_decimal::Decimal;
 
-use super::ApiResult;
+use super::{ApiResult, Paginated};
 use crate::{model::fiat_wallet::FiatWalletTransaction, ApiError};
 
 #[derive(Deserialize)]
@@ -13,8 +13,8 @@ pub struct FiatWalletTxResponse {
     links: Links,
 }
 
-impl FiatWalletTxResponse {
-    pub fn next_page(&self) -> Option<usize> {
+impl Paginated for FiatWalletTxResponse {
+    fn next_page(&self) -> Option<usize> {
         if let Some(next) = &self.links.next {
             let rgx = regex!(r"page=([0-9]+)");
             rgx.captures(next).and_then(|captures| {
@@ -26,7 +26,9 @@ impl FiatWalletTxResponse {
             None
         }
     }
+}
 
+impl FiatWalletTxResponse {
     pub fn into_transactions(self) -> ApiResult<Vec<FiatWalletTransaction>> {
         let mut txs = Vec::with_capacity(self.data.len());
         for tx in self.data.into_iter() {
@@ -37,6 +39,18 @@ impl FiatWalletTxResponse {
     }
 }
 
+/// Response of the single transaction endpoint
+#[derive(Deserialize)]
+pub struct SingleFiatWalletTxResponse {
+    data: Data,
+}
+
+impl SingleFiatWalletTxResponse {
+    pub fn into_transaction(self) -> ApiResult<FiatWalletTransaction> {
+        FiatWalletTransaction::try_from(self.data)
+    }
+}
+
 impl TryFrom<Data> for FiatWalletTransaction {
     type Error = ApiError;
 
@@ -111,4 +125,41 @@ mod test {
 
         assert!(response.next_page().is_none());
     }
+
+    #[test]
+    fn should_parse_single_fiat_wallet_transaction() {
+        let response: SingleFiatWalletTxResponse = serde_json::from_str(
+            r#"{
+                "data": {
+                    "type": "fiat_wallet_transaction",
+                    "attributes": {
+                        "amount": "1000.00",
+                        "time": {
+                            "date_iso8601": "2023-01-02T12:00:00+01:00",
+                            "unix": "1672657200"
+                        },
+                        "fiat_id": "1",
+                        "fee": "0.00",
+                        "in_or_out": "incoming",
+                        "type": "deposit",
+                        "status": "finished",
+                        "fiat_wallet_id": "eur-wallet",
+                        "to_eur_rate": "1.00000000",
+                        "user_id": "user"
+                    },
+                    "id": "fiat-tx-1"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let tx = response.into_transaction().unwrap();
+        assert_eq!(tx.id, "fiat-tx-1");
+        assert_eq!(tx.amount, Decimal::new(1000, 0));
+        assert_eq!(tx.in_or_out, crate::model::InOrOut::Incoming);
+        assert_eq!(tx.status, crate::model::TransactionStatus::Finished);
+        assert_eq!(tx.transaction_type, crate::model::TransactionType::Deposit);
+        assert_eq!(tx.wallet_id, "eur-wallet");
+        assert_eq!(tx.user_id, "user");
+    }
 }
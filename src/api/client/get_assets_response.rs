@@ -1,5 +1,7 @@
 use lazy_regex::regex;
 
+use super::Paginated;
+
 use crate::model::{Asset, AssetClass};
 
 #[derive(Deserialize)]
@@ -8,8 +10,8 @@ pub struct GetAssetsResponse {
     links: Links,
 }
 
-impl GetAssetsResponse {
-    pub fn next_page(&self) -> Option<usize> {
+impl Paginated for GetAssetsResponse {
+    fn next_page(&self) -> Option<usize> {
         if let Some(next) = &self.links.next {
             let rgx = regex!(r"page=([0-9]+)");
             rgx.captures(next).and_then(|captures| {
@@ -21,7 +23,9 @@ impl GetAssetsResponse {
             None
         }
     }
+}
 
+impl GetAssetsResponse {
     pub fn into_assets(self, class: AssetClass) -> Vec<Asset> {
         let mut assets = Vec::with_capacity(self.data.len());
         for data in self.data.into_iter() {
@@ -0,0 +1,346 @@
+//! # Raw
+//!
+//! Access to the raw JSON:API attributes of the returned resources
+
+use std::collections::{HashMap, VecDeque};
+
+use futures_util::stream::TryStreamExt;
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map, Value};
+
+use super::{
+    AssetWalletResponse, Client, CryptoWalletResponse, CryptoWalletTxResponse, FiatWalletResponse,
+    FiatWalletTxResponse, GetAssetsResponse, Paginated, SingleCryptoWalletTxResponse,
+    SingleFiatWalletTxResponse, SingleTradeResponse, TradeResponse, TRADE_DEFAULT_PAGE_SIZE,
+};
+use crate::api::{ApiError, ApiResult, TradeFilter, TransactionFilter};
+use crate::model::crypto_wallet::CryptoWalletTransaction;
+use crate::model::fiat_wallet::FiatWalletTransaction;
+use crate::model::{Asset, AssetClass, AssetWallet, CryptoWallet, FiatWallet, Trade};
+
+/// View of the [`Client`] which returns each resource along with the raw JSON:API `attributes` object
+/// it was parsed from, to read the attributes the models don't map yet.
+///
+/// Get it with [`Client::with_raw`]
+#[derive(Clone, Copy)]
+pub struct RawClient<'a> {
+    client: &'a Client,
+}
+
+impl Client {
+    /// Get a view of the client returning the raw attributes of each resource along with it
+    pub fn with_raw(&self) -> RawClient<'_> {
+        RawClient { client: self }
+    }
+}
+
+impl RawClient<'_> {
+    /// Get asset wallets for user.
+    /// Requires APIKEY
+    pub async fn get_asset_wallets(&self) -> ApiResult<Vec<(AssetWallet, Value)>> {
+        let response: Raw<AssetWalletResponse> = self
+            .client
+            .send(self.client.request_with_auth("asset-wallets")?)
+            .await?;
+
+        response.pair(
+            |response| Ok(response.into_asset_wallets()),
+            |wallet| &wallet.id,
+        )
+    }
+
+    /// Get crypto wallets
+    /// Requires APIKEY
+    pub async fn get_crypto_wallets(&self) -> ApiResult<Vec<(CryptoWallet, Value)>> {
+        let response: Raw<CryptoWalletResponse> = self
+            .client
+            .send(self.client.request_with_auth("wallets")?)
+            .await?;
+
+        response.pair(
+            |response| Ok(response.into_crypto_wallets()),
+            |wallet| &wallet.id,
+        )
+    }
+
+    /// Get FIAT wallets
+    /// Requires APIKEY
+    pub async fn get_fiat_wallets(&self) -> ApiResult<Vec<(FiatWallet, Value)>> {
+        let response: Raw<FiatWalletResponse> = self
+            .client
+            .send(self.client.request_with_auth("fiatwallets")?)
+            .await?;
+
+        response.pair(
+            |response| Ok(response.into_fiat_wallets()),
+            |wallet| &wallet.id,
+        )
+    }
+
+    /// get user's trades matching `filter`.
+    /// Requires APIKEY
+    pub async fn get_trades(&self, filter: TradeFilter) -> ApiResult<Vec<(Trade, Value)>> {
+        filter
+            .apply(self.client.paginate_trades(
+                &filter,
+                TRADE_DEFAULT_PAGE_SIZE,
+                |response: Raw<TradeResponse>| {
+                    response.pair(TradeResponse::into_trades, |trade| &trade.id)
+                },
+            ))
+            .try_collect()
+            .await
+    }
+
+    /// get user's trade with the provided id.
+    /// Returns `ApiError::NotFound` if there is no such trade
+    /// Requires APIKEY
+    pub async fn get_trade(&self, id: &str) -> ApiResult<(Trade, Value)> {
+        let response: Raw<SingleTradeResponse> = self
+            .client
+            .send(self.client.request_with_auth(format!("trades/{id}"))?)
+            .await?;
+
+        single(response.pair(
+            |response| response.into_trade().map(|trade| vec![trade]),
+            |trade| &trade.id,
+        )?)
+    }
+
+    /// Get crypto wallet transactions matching `filter`
+    /// Requires APIKEY
+    pub async fn get_crypto_wallet_transactions(
+        &self,
+        filter: TransactionFilter,
+    ) -> ApiResult<Vec<(CryptoWalletTransaction, Value)>> {
+        filter
+            .apply(self.client.paginate_crypto_wallet_transactions(
+                &filter,
+                TRADE_DEFAULT_PAGE_SIZE,
+                |response: Raw<CryptoWalletTxResponse>| {
+                    response.pair(CryptoWalletTxResponse::into_transactions, |tx| &tx.id)
+                },
+            ))
+            .try_collect()
+            .await
+    }
+
+    /// Get crypto wallet transaction with the provided id.
+    /// Returns `ApiError::NotFound` if there is no such transaction
+    /// Requires APIKEY
+    pub async fn get_crypto_wallet_transaction(
+        &self,
+        id: &str,
+    ) -> ApiResult<(CryptoWalletTransaction, Value)> {
+        let response: Raw<SingleCryptoWalletTxResponse> = self
+            .client
+            .send(
+                self.client
+                    .request_with_auth(format!("wallets/transactions/{id}"))?,
+            )
+            .await?;
+
+        single(response.pair(
+            |response| response.into_transaction().map(|tx| vec![tx]),
+            |tx| &tx.id,
+        )?)
+    }
+
+    /// Get fiat wallet transactions matching `filter`
+    /// Requires APIKEY
+    pub async fn get_fiat_wallet_transactions(
+        &self,
+        filter: TransactionFilter,
+    ) -> ApiResult<Vec<(FiatWalletTransaction, Value)>> {
+        filter
+            .apply(self.client.paginate_fiat_wallet_transactions(
+                &filter,
+                TRADE_DEFAULT_PAGE_SIZE,
+                |response: Raw<FiatWalletTxResponse>| {
+                    response.pair(FiatWalletTxResponse::into_transactions, |tx| &tx.id)
+                },
+            ))
+            .try_collect()
+            .await
+    }
+
+    /// Get fiat wallet transaction with the provided id.
+    /// Returns `ApiError::NotFound` if there is no such transaction
+    /// Requires APIKEY
+    pub async fn get_fiat_wallet_transaction(
+        &self,
+        id: &str,
+    ) -> ApiResult<(FiatWalletTransaction, Value)> {
+        let response: Raw<SingleFiatWalletTxResponse> = self
+            .client
+            .send(
+                self.client
+                    .request_with_auth(format!("fiatwallets/transactions/{id}"))?,
+            )
+            .await?;
+
+        single(response.pair(
+            |response| response.into_transaction().map(|tx| vec![tx]),
+            |tx| &tx.id,
+        )?)
+    }
+
+    /// Get assets available on Bitpanda by class
+    pub async fn get_assets(&self, asset_class: AssetClass) -> ApiResult<Vec<(Asset, Value)>> {
+        self.client
+            .paginate_assets(asset_class, move |response: Raw<GetAssetsResponse>| {
+                response.pair(
+                    |response| Ok(response.into_assets(asset_class)),
+                    |asset| &asset.id,
+                )
+            })
+            .try_collect()
+            .await
+    }
+}
+
+/// A response deserialized along with the raw `attributes` of the resources it contains, by id
+pub(super) struct Raw<P> {
+    response: P,
+    attributes: HashMap<String, Value>,
+}
+
+impl<'de, P> Deserialize<'de> for Raw<P>
+where
+    P: DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        let response = P::deserialize(&value).map_err(D::Error::custom)?;
+        let mut attributes = HashMap::new();
+        collect_attributes(&value, &mut attributes);
+
+        Ok(Self {
+            response,
+            attributes,
+        })
+    }
+}
+
+impl<P> Paginated for Raw<P>
+where
+    P: Paginated,
+{
+    fn next_page(&self) -> Option<usize> {
+        self.response.next_page()
+    }
+}
+
+impl<P> Raw<P> {
+    /// Convert the response with `convert`, pairing each item with the attributes of the resource with its id.
+    /// Items without attributes are paired with `Value::Null`
+    pub fn pair<T>(
+        mut self,
+        convert: impl FnOnce(P) -> ApiResult<Vec<T>>,
+        id: impl Fn(&T) -> &str,
+    ) -> ApiResult<Vec<(T, Value)>> {
+        Ok(convert(self.response)?
+            .into_iter()
+            .map(|item| {
+                let attributes = self.attributes.remove(id(&item)).unwrap_or_default();
+                (item, attributes)
+            })
+            .collect())
+    }
+}
+
+/// Take the only item of a single resource response
+fn single<T>(items: Vec<T>) -> ApiResult<T> {
+    items
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::UnexpectedValue("empty resource response".to_string()))
+}
+
+/// Collect the attributes of the resources in `value`, breadth-first, so outer resources win over
+/// nested ones with the same id
+fn collect_attributes(value: &Value, attributes: &mut HashMap<String, Value>) {
+    let mut queue = VecDeque::from([value]);
+    while let Some(value) = queue.pop_front() {
+        match value {
+            Value::Object(object) => {
+                if let Some((id, attrs)) = resource(object) {
+                    attributes
+                        .entry(id.to_string())
+                        .or_insert_with(|| attrs.clone());
+                }
+                queue.extend(object.values());
+            }
+            Value::Array(values) => queue.extend(values),
+            _ => {}
+        }
+    }
+}
+
+fn resource(object: &Map<String, Value>) -> Option<(&str, &Value)> {
+    let id = object.get("id")?.as_str()?;
+    let attributes = object.get("attributes").filter(|attrs| attrs.is_object())?;
+
+    Some((id, attributes))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[derive(Deserialize)]
+    struct Page {
+        data: Vec<Item>,
+    }
+
+    #[derive(Deserialize)]
+    struct Item {
+        id: String,
+    }
+
+    #[test]
+    fn should_pair_items_with_raw_attributes() {
+        let raw: Raw<Page> = serde_json::from_value(json!({
+            "data": [
+                {
+                    "id": "1",
+                    "attributes": {
+                        "best_price": "10.5",
+                        "related": { "id": "2", "attributes": { "nested": true } }
+                    }
+                },
+                { "id": "2", "attributes": { "best_price": "11" } },
+                { "id": "3" }
+            ]
+        }))
+        .unwrap();
+        let items = raw
+            .pair(
+                |page| Ok(page.data.into_iter().map(|item| item.id).collect()),
+                |id| id.as_str(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            items,
+            vec![
+                (
+                    "1".to_string(),
+                    json!({
+                        "best_price": "10.5",
+                        "related": { "id": "2", "attributes": { "nested": true } }
+                    })
+                ),
+                ("2".to_string(), json!({ "best_price": "11" })),
+                ("3".to_string(), Value::Null),
+            ]
+        );
+    }
+}
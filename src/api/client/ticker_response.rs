@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::model::Ticker;
+
+/// Prices by asset symbol and then by fiat currency symbol
+#[derive(Deserialize)]
+pub struct TickerResponse(HashMap<String, HashMap<String, Decimal>>);
+
+impl TickerResponse {
+    pub fn into_ticker(self) -> Ticker {
+        Ticker { prices: self.0 }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{Asset, AssetClass};
+
+    use pretty_assertions::assert_eq;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn should_parse_ticker() {
+        let response: TickerResponse = serde_json::from_str(
+            r#"{
+                "BTC": { "EUR": "25342.12", "USD": "27340.55", "CHF": "24612.00" },
+                "XAU": { "EUR": "59.45", "USD": "64.12" }
+            }"#,
+        )
+        .unwrap();
+
+        let ticker = response.into_ticker();
+        assert_eq!(ticker.price("BTC", "EUR"), Some(dec!(25342.12)));
+        assert_eq!(ticker.price("XAU", "USD"), Some(dec!(64.12)));
+        assert_eq!(ticker.price("XAU", "CHF"), None);
+        assert_eq!(ticker.price("ETH", "EUR"), None);
+        assert_eq!(ticker.prices_of("BTC").unwrap().len(), 3);
+        let asset = Asset {
+            id: "1".to_string(),
+            pid: "pid".to_string(),
+            name: "Bitcoin".to_string(),
+            symbol: "BTC".to_string(),
+            r#type: AssetClass::Cryptocurrency,
+        };
+        assert_eq!(ticker.asset_price(&asset, "USD"), Some(dec!(27340.55)));
+        let prices = ticker.prices_in("CHF");
+        assert_eq!(prices.len(), 1);
+        assert_eq!(prices.get("BTC").copied(), Some(dec!(24612.00)));
+    }
+}
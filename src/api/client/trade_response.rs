@@ -4,7 +4,7 @@ use chrono::{DateTime, FixedOffset};
 use lazy_regex::regex;
 use rust_decimal::Decimal;
 
-use super::ApiResult;
+use super::{ApiResult, Paginated};
 
 use crate::{
     model::{Trade, TradeStatus, TradeType},
@@ -17,8 +17,8 @@ pub struct TradeResponse {
     links: Links,
 }
 
-impl TradeResponse {
-    pub fn next_page(&self) -> Option<usize> {
+impl Paginated for TradeResponse {
+    fn next_page(&self) -> Option<usize> {
         if let Some(next) = &self.links.next {
             let rgx = regex!(r"page=([0-9]+)");
             rgx.captures(next).and_then(|captures| {
@@ -32,6 +32,12 @@ impl TradeResponse {
     }
 }
 
+/// Response of the single trade endpoint
+#[derive(Deserialize)]
+pub struct SingleTradeResponse {
+    data: Data,
+}
+
 #[derive(Deserialize)]
 struct Links {
     next: Option<String>,
@@ -76,6 +82,12 @@ impl TradeResponse {
     }
 }
 
+impl SingleTradeResponse {
+    pub fn into_trade(self) -> ApiResult<Trade> {
+        Trade::try_from(self.data)
+    }
+}
+
 impl TryFrom<Data> for Trade {
     type Error = ApiError;
 
@@ -131,4 +143,78 @@ mod test {
 
         assert!(response.next_page().is_none());
     }
+
+    #[test]
+    fn should_parse_single_trade() {
+        let response: SingleTradeResponse = serde_json::from_str(
+            r#"{
+                "data": {
+                    "type": "trade",
+                    "attributes": {
+                        "status": "finished",
+                        "type": "buy",
+                        "cryptocoin_id": "1",
+                        "cryptocoin_symbol": "BTC",
+                        "fiat_id": "1",
+                        "amount_fiat": "100.00",
+                        "amount_cryptocoin": "0.00400000",
+                        "fiat_to_eur_rate": "1.00000000",
+                        "wallet_id": "wallet-1",
+                        "fiat_wallet_id": "fiat-wallet-1",
+                        "time": {
+                            "date_iso8601": "2023-01-03T10:15:00+01:00",
+                            "unix": "1672737300"
+                        },
+                        "price": "25000.00",
+                        "is_swap": false
+                    },
+                    "id": "trade-1"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let trade = response.into_trade().unwrap();
+        assert_eq!(trade.id, "trade-1");
+        assert_eq!(trade.symbol, "BTC");
+        assert_eq!(trade.status, TradeStatus::Finished);
+        assert_eq!(trade.r#type, TradeType::Buy);
+        assert_eq!(trade.amount_asset, Decimal::new(4, 3));
+        assert!(trade.related_swap_trade.is_none());
+    }
+
+    #[test]
+    fn should_keep_trades_with_unknown_status() {
+        let response: SingleTradeResponse = serde_json::from_str(
+            r#"{
+                "data": {
+                    "type": "trade",
+                    "attributes": {
+                        "status": "settled",
+                        "type": "sell",
+                        "cryptocoin_id": "1",
+                        "cryptocoin_symbol": "BTC",
+                        "fiat_id": "1",
+                        "amount_fiat": "100.00",
+                        "amount_cryptocoin": "0.00400000",
+                        "fiat_to_eur_rate": "1.00000000",
+                        "wallet_id": "wallet-1",
+                        "fiat_wallet_id": "fiat-wallet-1",
+                        "time": {
+                            "date_iso8601": "2023-01-03T10:15:00+01:00",
+                            "unix": "1672737300"
+                        },
+                        "price": "25000.00",
+                        "is_swap": false
+                    },
+                    "id": "trade-2"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let trade = response.into_trade().unwrap();
+        assert_eq!(trade.status, TradeStatus::Unknown("settled".to_string()));
+        assert_eq!(trade.r#type, TradeType::Sell);
+    }
 }
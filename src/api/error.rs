@@ -1,23 +1,38 @@
 //! # Error
 
+use std::time::Duration;
+
 use reqwest::Error as HttpError;
 use serde_json::Error as JsonError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum ApiError {
+    #[error("Cassette error: {0}")]
+    Cassette(String),
     #[error("Http error: {0}")]
     Http(HttpError),
     #[error("No such asset: {0}")]
     NoSuchAsset(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
     #[error("Parse error: {0}")]
     Parse(JsonError),
-    #[error("Server error")]
-    ServerError,
+    #[error("Rate limited (retry after: {retry_after:?}): {message}")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        message: String,
+    },
+    #[error("Server error ({status}): {body}")]
+    ServerError { status: u16, body: String },
+    #[error("Transport error: {message}")]
+    Transport { message: String, transient: bool },
+    #[error("Unexpected status ({status}): {message}")]
+    UnexpectedStatus { status: u16, message: String },
     #[error("Unexpected value: {0}")]
     UnexpectedValue(String),
-    #[error("Client unauthorized")]
-    Unauthorized,
+    #[error("Client unauthorized: {0}")]
+    Unauthorized(String),
 }
 
 impl From<HttpError> for ApiError {
@@ -0,0 +1,396 @@
+//! # Filter
+//!
+//! Filters for paginated requests
+
+use chrono::{DateTime, FixedOffset};
+use futures_util::future;
+use futures_util::stream::{Stream, TryStreamExt};
+
+use super::ApiResult;
+use crate::model::crypto_wallet::CryptoWalletTransaction;
+use crate::model::fiat_wallet::FiatWalletTransaction;
+use crate::model::{Trade, TradeType, TransactionStatus, TransactionType};
+
+/// Filter for trades
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct TradeFilter {
+    /// Only get trades of this type
+    pub trade_type: Option<TradeType>,
+    /// Only get trades executed at or after this time
+    pub from: Option<DateTime<FixedOffset>>,
+    /// Only get trades executed before this time
+    pub to: Option<DateTime<FixedOffset>>,
+    /// Only get trades more recent than the one with this id
+    pub since_id: Option<String>,
+    /// Only get trades older than the one with this id
+    pub until_id: Option<String>,
+}
+
+impl TradeFilter {
+    /// Only get trades of the provided type
+    pub fn trade_type(mut self, trade_type: TradeType) -> Self {
+        self.trade_type = Some(trade_type);
+
+        self
+    }
+
+    /// Only get trades executed at or after `from`.
+    /// Since trades are returned from the most recent one, pagination stops at the first older trade
+    pub fn from(mut self, from: impl Into<DateTime<FixedOffset>>) -> Self {
+        self.from = Some(from.into());
+
+        self
+    }
+
+    /// Only get trades executed before `to`
+    pub fn to(mut self, to: impl Into<DateTime<FixedOffset>>) -> Self {
+        self.to = Some(to.into());
+
+        self
+    }
+
+    /// Only get trades more recent than the one with id `id`.
+    /// Pagination stops as soon as `id` is encountered, which makes polling for new trades cheap
+    pub fn since_id(mut self, id: impl ToString) -> Self {
+        self.since_id = Some(id.to_string());
+
+        self
+    }
+
+    /// Only get trades older than the one with id `id`
+    pub fn until_id(mut self, id: impl ToString) -> Self {
+        self.until_id = Some(id.to_string());
+
+        self
+    }
+
+    /// Get query arguments for the filter
+    pub(crate) fn query_args(&self) -> String {
+        self.trade_type
+            .map(|t| format!("&type={t}"))
+            .unwrap_or_default()
+    }
+
+    /// Apply the client-side part of the filter to a stream of trades
+    pub(crate) fn apply<'a, T>(
+        &self,
+        stream: impl Stream<Item = ApiResult<T>> + 'a,
+    ) -> impl Stream<Item = ApiResult<T>> + 'a
+    where
+        T: HistoryItem + 'a,
+    {
+        apply_bounds(
+            stream,
+            self.from,
+            self.to,
+            self.since_id.clone(),
+            self.until_id.clone(),
+        )
+    }
+}
+
+/// Filter for crypto and fiat wallet transactions
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct TransactionFilter {
+    /// Only get transactions of this type
+    pub transaction_type: Option<TransactionType>,
+    /// Only get transactions with this status
+    pub status: Option<TransactionStatus>,
+    /// Only get transactions executed at or after this time
+    pub from: Option<DateTime<FixedOffset>>,
+    /// Only get transactions executed before this time
+    pub to: Option<DateTime<FixedOffset>>,
+    /// Only get transactions more recent than the one with this id
+    pub since_id: Option<String>,
+    /// Only get transactions older than the one with this id
+    pub until_id: Option<String>,
+}
+
+impl TransactionFilter {
+    /// Only get transactions of the provided type
+    pub fn transaction_type(mut self, transaction_type: TransactionType) -> Self {
+        self.transaction_type = Some(transaction_type);
+
+        self
+    }
+
+    /// Only get transactions with the provided status
+    pub fn status(mut self, status: TransactionStatus) -> Self {
+        self.status = Some(status);
+
+        self
+    }
+
+    /// Only get transactions executed at or after `from`.
+    /// Since transactions are returned from the most recent one, pagination stops at the first older transaction
+    pub fn from(mut self, from: impl Into<DateTime<FixedOffset>>) -> Self {
+        self.from = Some(from.into());
+
+        self
+    }
+
+    /// Only get transactions executed before `to`
+    pub fn to(mut self, to: impl Into<DateTime<FixedOffset>>) -> Self {
+        self.to = Some(to.into());
+
+        self
+    }
+
+    /// Only get transactions more recent than the one with id `id`.
+    /// Pagination stops as soon as `id` is encountered, which makes polling for new transactions cheap
+    pub fn since_id(mut self, id: impl ToString) -> Self {
+        self.since_id = Some(id.to_string());
+
+        self
+    }
+
+    /// Only get transactions older than the one with id `id`
+    pub fn until_id(mut self, id: impl ToString) -> Self {
+        self.until_id = Some(id.to_string());
+
+        self
+    }
+
+    /// Get query arguments for the filter
+    pub(crate) fn query_args(&self) -> String {
+        let transaction_type_arg = self
+            .transaction_type
+            .as_ref()
+            .map(|t| format!("&type={t}"))
+            .unwrap_or_default();
+
+        let status_arg = self
+            .status
+            .as_ref()
+            .map(|s| format!("&status={s}"))
+            .unwrap_or_default();
+
+        format!("{transaction_type_arg}{status_arg}")
+    }
+
+    /// Apply the client-side part of the filter to a stream of transactions
+    pub(crate) fn apply<'a, T>(
+        &self,
+        stream: impl Stream<Item = ApiResult<T>> + 'a,
+    ) -> impl Stream<Item = ApiResult<T>> + 'a
+    where
+        T: HistoryItem + 'a,
+    {
+        apply_bounds(
+            stream,
+            self.from,
+            self.to,
+            self.since_id.clone(),
+            self.until_id.clone(),
+        )
+    }
+}
+
+/// An item of the user history, returned from the most recent one by the API
+pub(crate) trait HistoryItem {
+    /// Bitpanda id of the item
+    fn id(&self) -> &str;
+
+    /// Time the item was executed at
+    fn datetime(&self) -> DateTime<FixedOffset>;
+}
+
+impl HistoryItem for Trade {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn datetime(&self) -> DateTime<FixedOffset> {
+        self.datetime
+    }
+}
+
+impl HistoryItem for CryptoWalletTransaction {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn datetime(&self) -> DateTime<FixedOffset> {
+        self.datetime
+    }
+}
+
+impl HistoryItem for FiatWalletTransaction {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn datetime(&self) -> DateTime<FixedOffset> {
+        self.datetime
+    }
+}
+
+/// Items returned along with their raw attributes
+impl<T> HistoryItem for (T, serde_json::Value)
+where
+    T: HistoryItem,
+{
+    fn id(&self) -> &str {
+        self.0.id()
+    }
+
+    fn datetime(&self) -> DateTime<FixedOffset> {
+        self.0.datetime()
+    }
+}
+
+/// Keep the items in `[from, to)` which come after `until_id` and before `since_id`.
+/// The stream is stopped at the first item older than `from` or at `since_id`
+fn apply_bounds<'a, T>(
+    stream: impl Stream<Item = ApiResult<T>> + 'a,
+    from: Option<DateTime<FixedOffset>>,
+    to: Option<DateTime<FixedOffset>>,
+    since_id: Option<String>,
+    until_id: Option<String>,
+) -> impl Stream<Item = ApiResult<T>> + 'a
+where
+    T: HistoryItem + 'a,
+{
+    let mut until_reached = until_id.is_none();
+    stream
+        .try_take_while(move |item| {
+            future::ready(Ok(since_id.as_deref() != Some(item.id())
+                && from.map_or(true, |from| item.datetime() >= from)))
+        })
+        .try_filter(move |item| {
+            let keep = until_reached && to.map_or(true, |to| item.datetime() < to);
+            if until_id.as_deref() == Some(item.id()) {
+                until_reached = true;
+            }
+            future::ready(keep)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use futures_util::stream;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_build_query_args() {
+        assert_eq!(TransactionFilter::default().query_args(), "");
+        assert_eq!(
+            TransactionFilter::default()
+                .transaction_type(TransactionType::Buy)
+                .status(TransactionStatus::Finished)
+                .query_args(),
+            "&type=buy&status=finished"
+        );
+        assert_eq!(TradeFilter::default().query_args(), "");
+        assert_eq!(
+            TradeFilter::default()
+                .trade_type(TradeType::Sell)
+                .query_args(),
+            "&type=sell"
+        );
+    }
+
+    #[test]
+    fn should_send_refund_transaction_type() {
+        assert_eq!(
+            TransactionFilter::default()
+                .transaction_type(TransactionType::Refund)
+                .query_args(),
+            "&type=refund"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_filter_by_date_range() {
+        let filtered = collect_ids(apply_bounds(
+            history(),
+            Some("2023-01-01T00:00:00+00:00".parse().unwrap()),
+            Some("2023-03-01T00:00:00+00:00".parse().unwrap()),
+            None,
+            None,
+        ))
+        .await;
+
+        assert_eq!(filtered, vec!["3", "4"]);
+    }
+
+    #[tokio::test]
+    async fn should_filter_by_id() {
+        assert_eq!(
+            collect_ids(apply_bounds(
+                history(),
+                None,
+                None,
+                Some("3".to_string()),
+                None
+            ))
+            .await,
+            vec!["1", "2"]
+        );
+        assert_eq!(
+            collect_ids(apply_bounds(
+                history(),
+                None,
+                None,
+                None,
+                Some("3".to_string())
+            ))
+            .await,
+            vec!["4", "5", "6"]
+        );
+        assert_eq!(
+            collect_ids(apply_bounds(
+                history(),
+                None,
+                None,
+                Some("5".to_string()),
+                Some("2".to_string())
+            ))
+            .await,
+            vec!["3", "4"]
+        );
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Item {
+        id: String,
+        datetime: DateTime<FixedOffset>,
+    }
+
+    impl HistoryItem for Item {
+        fn id(&self) -> &str {
+            &self.id
+        }
+
+        fn datetime(&self) -> DateTime<FixedOffset> {
+            self.datetime
+        }
+    }
+
+    fn history() -> impl Stream<Item = ApiResult<Item>> {
+        stream::iter(
+            [
+                "2023-05-01T00:00:00+00:00",
+                "2023-03-01T00:00:00+00:00",
+                "2023-02-01T00:00:00+00:00",
+                "2023-01-01T00:00:00+00:00",
+                "2022-12-01T00:00:00+00:00",
+                "2022-11-15T00:00:00+00:00",
+            ]
+            .into_iter()
+            .enumerate()
+            .map(|(i, datetime)| {
+                Ok(Item {
+                    id: (i + 1).to_string(),
+                    datetime: datetime.parse().unwrap(),
+                })
+            }),
+        )
+    }
+
+    async fn collect_ids(stream: impl Stream<Item = ApiResult<Item>>) -> Vec<String> {
+        stream.map_ok(|item| item.id).try_collect().await.unwrap()
+    }
+}
@@ -1,10 +1,24 @@
 //! # Bitpanda API module
 
+mod builder;
+mod cassette;
 mod client;
 mod error;
+mod filter;
+mod rate_limit;
+mod retry;
+mod transport;
 
-pub use client::Client;
+pub use builder::ClientBuilder;
+pub use cassette::{Cassette, Interaction, RecordingTransport, ReplayTransport};
+pub use client::{Client, RawClient};
 pub use error::ApiError;
+#[cfg(feature = "store")]
+pub(crate) use filter::HistoryItem;
+pub use filter::{TradeFilter, TransactionFilter};
+pub use rate_limit::RateLimit;
+pub use retry::RetryPolicy;
+pub use transport::{HttpRequest, HttpResponse, ReqwestTransport, Transport};
 
 /// Api result
 pub type ApiResult<T> = Result<T, ApiError>;
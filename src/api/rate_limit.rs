@@ -0,0 +1,150 @@
+//! # Rate limit
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// Describes the request quota for a Bitpanda host.
+///
+/// The quota is enforced with a token bucket, which is refilled with `requests` tokens every `per`
+/// interval and can hold up to `burst` tokens
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RateLimit {
+    requests: u32,
+    per: Duration,
+    burst: u32,
+}
+
+impl RateLimit {
+    /// Allow `requests` requests every `per` interval.
+    /// The burst is set to `requests` by default
+    pub fn new(requests: u32, per: Duration) -> Self {
+        let requests = requests.max(1);
+        Self {
+            requests,
+            per,
+            burst: requests,
+        }
+    }
+
+    /// Allow `requests` requests per second
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    /// Allow `requests` requests per minute
+    pub fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+
+    /// Set the maximum amount of requests which can be sent back to back
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+
+        self
+    }
+
+    /// Tokens refilled per second
+    fn rate(&self) -> f64 {
+        f64::from(self.requests) / self.per.as_secs_f64()
+    }
+}
+
+/// Token bucket shared between all the clones of a client
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: f64::from(limit.burst),
+                last_refill: Instant::now(),
+            })),
+        }
+    }
+
+    /// Take a token from the bucket, waiting until it becomes available.
+    ///
+    /// Tokens are reserved in order of arrival, so the bucket may go negative while callers wait
+    pub async fn acquire(&self) {
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap();
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+            bucket.tokens =
+                (bucket.tokens + elapsed * self.limit.rate()).min(f64::from(self.limit.burst));
+            bucket.last_refill = now;
+            bucket.tokens -= 1.0;
+            if bucket.tokens >= 0.0 {
+                None
+            } else {
+                Some(Duration::from_secs_f64(-bucket.tokens / self.limit.rate()))
+            }
+        };
+
+        if let Some(wait) = wait {
+            trace!("rate limit reached; waiting {wait:?}");
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_build_rate_limit() {
+        let limit = RateLimit::per_second(10);
+        assert_eq!(limit.requests, 10);
+        assert_eq!(limit.burst, 10);
+        assert_eq!(limit.rate(), 10.0);
+        let limit = RateLimit::per_minute(120).burst(5);
+        assert_eq!(limit.burst, 5);
+        assert_eq!(limit.rate(), 2.0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn should_not_wait_within_burst() {
+        let limiter = RateLimiter::new(RateLimit::per_second(1).burst(3));
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn should_wait_when_quota_is_exceeded() {
+        let limiter = RateLimiter::new(RateLimit::per_second(2).burst(1));
+        let start = Instant::now();
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() >= Duration::from_secs(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn should_share_bucket_between_clones() {
+        let limiter = RateLimiter::new(RateLimit::per_second(1).burst(1));
+        let other = limiter.clone();
+        let start = Instant::now();
+        limiter.acquire().await;
+        other.acquire().await;
+        assert!(start.elapsed() >= Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,206 @@
+//! # Retry policy
+
+use std::time::Duration;
+
+use super::ApiError;
+
+const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Describes how failed requests are retried by the client.
+///
+/// Requests failing with a timeout, a connection error, a server error (5xx) or a rate limit (429)
+/// are retried up to `max_attempts` times with an exponential backoff.
+/// If the server provides a `Retry-After` header, its value is used as delay instead, capped to `max_delay`.
+///
+/// The default policy never retries
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: usize,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which never retries failed requests
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    /// A policy which performs at most `max_attempts` attempts for each request,
+    /// with a base delay of 500ms, a max delay of 30 seconds and jitter enabled
+    pub fn new(max_attempts: usize) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            jitter: true,
+        }
+    }
+
+    /// Set the delay before the first retry. The delay is doubled at each retry
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+
+        self
+    }
+
+    /// Set the maximum delay between two attempts, `Retry-After` included
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+
+        self
+    }
+
+    /// Enable or disable the random jitter applied to the backoff delay
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+
+        self
+    }
+
+    /// Get the maximum amount of attempts for a request
+    pub fn max_attempts(&self) -> usize {
+        self.max_attempts
+    }
+
+    /// Returns whether `attempt` (starting from 1) failed with `error` should be retried
+    pub(crate) fn should_retry(&self, attempt: usize, error: &ApiError) -> bool {
+        attempt < self.max_attempts && Self::is_transient(error)
+    }
+
+    /// Get the delay to wait after the attempt `attempt` (starting from 1) failed with `error`
+    pub(crate) fn delay(&self, attempt: usize, error: &ApiError) -> Duration {
+        if let ApiError::RateLimited {
+            retry_after: Some(retry_after),
+            ..
+        } = error
+        {
+            return (*retry_after).min(self.max_delay);
+        }
+
+        let exponent = attempt.saturating_sub(1).min(31) as u32;
+        let delay = self
+            .base_delay
+            .saturating_mul(2_u32.saturating_pow(exponent))
+            .min(self.max_delay);
+
+        if self.jitter {
+            // equal jitter: keep half of the delay and randomize the other half
+            let half = delay / 2;
+            half + half.mul_f64(fastrand::f64())
+        } else {
+            delay
+        }
+    }
+
+    fn is_transient(error: &ApiError) -> bool {
+        match error {
+            ApiError::Http(err) => err.is_timeout() || err.is_connect(),
+            ApiError::RateLimited { .. } | ApiError::ServerError { .. } => true,
+            ApiError::Transport { transient, .. } => *transient,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_never_retry_by_default() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.max_attempts(), 1);
+        assert!(!policy.should_retry(1, &server_error()));
+    }
+
+    #[test]
+    fn should_retry_transient_errors() {
+        let policy = RetryPolicy::new(3);
+        assert!(policy.should_retry(1, &server_error()));
+        assert!(policy.should_retry(
+            2,
+            &ApiError::RateLimited {
+                retry_after: None,
+                message: String::new(),
+            }
+        ));
+        assert!(!policy.should_retry(3, &server_error()));
+        assert!(!policy.should_retry(1, &ApiError::Unauthorized(String::new())));
+        assert!(!policy.should_retry(1, &ApiError::NotFound(String::new())));
+    }
+
+    #[test]
+    fn should_backoff_exponentially() {
+        let policy = RetryPolicy::new(10)
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(1000))
+            .jitter(false);
+        assert_eq!(policy.delay(1, &server_error()), Duration::from_millis(100));
+        assert_eq!(policy.delay(2, &server_error()), Duration::from_millis(200));
+        assert_eq!(policy.delay(3, &server_error()), Duration::from_millis(400));
+        assert_eq!(
+            policy.delay(5, &server_error()),
+            Duration::from_millis(1000)
+        );
+        assert_eq!(
+            policy.delay(64, &server_error()),
+            Duration::from_millis(1000)
+        );
+    }
+
+    #[test]
+    fn should_apply_jitter() {
+        let policy = RetryPolicy::new(10)
+            .base_delay(Duration::from_millis(100))
+            .jitter(true);
+        for _ in 0..32 {
+            let delay = policy.delay(2, &server_error());
+            assert!(delay >= Duration::from_millis(100));
+            assert!(delay <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn should_honor_retry_after() {
+        let policy = RetryPolicy::new(3).max_delay(Duration::from_secs(1));
+        assert_eq!(
+            policy.delay(
+                1,
+                &ApiError::RateLimited {
+                    retry_after: Some(Duration::from_millis(200)),
+                    message: String::new(),
+                }
+            ),
+            Duration::from_millis(200)
+        );
+        // capped to the max delay
+        assert_eq!(
+            policy.delay(
+                1,
+                &ApiError::RateLimited {
+                    retry_after: Some(Duration::from_secs(10)),
+                    message: String::new(),
+                }
+            ),
+            Duration::from_secs(1)
+        );
+    }
+
+    fn server_error() -> ApiError {
+        ApiError::ServerError {
+            status: 503,
+            body: String::new(),
+        }
+    }
+}
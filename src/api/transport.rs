@@ -0,0 +1,124 @@
+//! # Transport
+//!
+//! HTTP transport the client sends its requests through
+
+use futures_util::future::BoxFuture;
+
+use super::ApiResult;
+
+/// A GET request to the Bitpanda API
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HttpRequest {
+    /// Absolute URL, query included
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl HttpRequest {
+    /// Create a request to `url` without headers
+    pub fn get(url: impl ToString) -> Self {
+        Self {
+            url: url.to_string(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Get the value of the header `name`, ignoring its case
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// A response of the Bitpanda API
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// Get the value of the header `name`, ignoring its case
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// Sends the requests of the client.
+///
+/// Implement this trait to plug in another HTTP client, a middleware stack or an in-process fake,
+/// then provide it to [`super::ClientBuilder::transport`].
+/// Failures which are not HTTP responses should be reported as [`super::ApiError::Transport`]
+pub trait Transport: Send + Sync {
+    /// Send `request` and return the response, whatever its status
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, ApiResult<HttpResponse>>;
+}
+
+/// Transport backed by [`reqwest`]; this is the default transport
+#[derive(Debug, Default, Clone)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    /// Use the provided http client, e.g. to configure proxies, root certificates and timeouts
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, ApiResult<HttpResponse>> {
+        Box::pin(async move {
+            let mut builder = self.client.get(&request.url);
+            for (name, value) in &request.headers {
+                builder = builder.header(name, value);
+            }
+            let response = builder.send().await?;
+            let status = response.status().as_u16();
+            let headers = response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| {
+                    value
+                        .to_str()
+                        .ok()
+                        .map(|value| (name.to_string(), value.to_string()))
+                })
+                .collect();
+            let body = response.text().await?;
+
+            Ok(HttpResponse {
+                status,
+                headers,
+                body,
+            })
+        })
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use reqwest::header::RETRY_AFTER;
+
+    #[test]
+    fn should_find_header_ignoring_case() {
+        let response = HttpResponse {
+            status: 429,
+            headers: vec![(RETRY_AFTER.to_string(), "30".to_string())],
+            body: String::new(),
+        };
+        assert_eq!(response.header("Retry-After"), Some("30"));
+        assert_eq!(response.header("content-type"), None);
+    }
+}
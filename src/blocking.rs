@@ -0,0 +1,395 @@
+//! # Blocking
+//!
+//! Synchronous Bitpanda api client, available with the `blocking` feature.
+//!
+//! The blocking [`Client`] wraps the async [`crate::Client`] and drives its requests on a private
+//! current-thread tokio runtime, so it can be used by plain synchronous programs.
+//! It must not be used from within an async runtime, since blocking on a future there panics.
+//!
+//! ```rust,no_run
+//! use bitpanda_api::blocking::Client;
+//!
+//! let client = Client::default().x_apikey(std::env::var("X_API_KEY").unwrap());
+//! let trades = client.get_trades().unwrap();
+//! ```
+
+use std::future::Future;
+use std::sync::Arc;
+
+use serde_json::Value;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::api::ApiResult;
+use crate::model::crypto_wallet::CryptoWalletTransaction;
+use crate::model::fiat_wallet::FiatWalletTransaction;
+use crate::model::ohlc::Period;
+use crate::model::{
+    Asset, AssetClass, AssetWallet, CryptoWallet, FiatWallet, OpenHighLowCloseChart, Ticker, Trade,
+    TransactionStatus, TransactionType,
+};
+use crate::{ClientBuilder, TradeFilter, TransactionFilter};
+
+/// Blocking Bitpanda api client.
+///
+/// Cloning the client is cheap and clones share the same runtime and transport
+#[derive(Clone)]
+pub struct Client {
+    inner: crate::Client,
+    runtime: Arc<Runtime>,
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new(crate::Client::default())
+    }
+}
+
+impl From<crate::Client> for Client {
+    fn from(client: crate::Client) -> Self {
+        Self::new(client)
+    }
+}
+
+impl Client {
+    /// Wrap the async `client`, configured through its [`ClientBuilder`]
+    pub fn new(client: crate::Client) -> Self {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to build blocking client runtime");
+
+        Self {
+            inner: client,
+            runtime: Arc::new(runtime),
+        }
+    }
+
+    /// Get a builder to configure the client; build it with [`Client::new`]
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Construct client with x-apikey
+    pub fn x_apikey(mut self, apikey: impl ToString) -> Self {
+        self.inner = self.inner.x_apikey(apikey);
+
+        self
+    }
+
+    /// Get the async client this client wraps
+    pub fn inner(&self) -> &crate::Client {
+        &self.inner
+    }
+
+    // requests
+
+    /// Get asset wallets for user.
+    /// Requires APIKEY
+    pub fn get_asset_wallets(&self) -> ApiResult<Vec<AssetWallet>> {
+        self.block_on(self.inner.get_asset_wallets())
+    }
+
+    /// Get crypto wallets
+    /// Requires APIKEY
+    pub fn get_crypto_wallets(&self) -> ApiResult<Vec<CryptoWallet>> {
+        self.block_on(self.inner.get_crypto_wallets())
+    }
+
+    /// Get FIAT wallets
+    /// Requires APIKEY
+    pub fn get_fiat_wallets(&self) -> ApiResult<Vec<FiatWallet>> {
+        self.block_on(self.inner.get_fiat_wallets())
+    }
+
+    /// get user's trades.
+    /// Requires APIKEY
+    pub fn get_trades(&self) -> ApiResult<Vec<Trade>> {
+        self.block_on(self.inner.get_trades())
+    }
+
+    /// get user's trades.
+    /// If max_results is specified, only the amount of trades specified are fetched
+    /// Requires APIKEY
+    pub fn get_trades_ex(&self, max_results: Option<usize>) -> ApiResult<Vec<Trade>> {
+        self.block_on(self.inner.get_trades_ex(max_results))
+    }
+
+    /// get user's trade with the provided id.
+    /// Returns `ApiError::NotFound` if there is no such trade
+    /// Requires APIKEY
+    pub fn get_trade(&self, id: &str) -> ApiResult<Trade> {
+        self.block_on(self.inner.get_trade(id))
+    }
+
+    /// get user's trades matching `filter`.
+    /// Requires APIKEY
+    pub fn get_trades_with_filter(&self, filter: TradeFilter) -> ApiResult<Vec<Trade>> {
+        self.block_on(self.inner.get_trades_with_filter(filter))
+    }
+
+    /// Get crypto wallet transactions
+    /// Requires APIKEY
+    pub fn get_crypto_wallet_transactions(&self) -> ApiResult<Vec<CryptoWalletTransaction>> {
+        self.block_on(self.inner.get_crypto_wallet_transactions())
+    }
+
+    /// Get crypto wallet transactions
+    /// if specified, get transactions with provided filters
+    /// Requires APIKEY
+    pub fn get_crypto_wallet_transactions_ex(
+        &self,
+        transaction_type: Option<TransactionType>,
+        status: Option<TransactionStatus>,
+        max_results: Option<usize>,
+    ) -> ApiResult<Vec<CryptoWalletTransaction>> {
+        self.block_on(self.inner.get_crypto_wallet_transactions_ex(
+            transaction_type,
+            status,
+            max_results,
+        ))
+    }
+
+    /// Get crypto wallet transaction with the provided id.
+    /// Returns `ApiError::NotFound` if there is no such transaction
+    /// Requires APIKEY
+    pub fn get_crypto_wallet_transaction(&self, id: &str) -> ApiResult<CryptoWalletTransaction> {
+        self.block_on(self.inner.get_crypto_wallet_transaction(id))
+    }
+
+    /// Get crypto wallet transactions matching `filter`
+    /// Requires APIKEY
+    pub fn get_crypto_wallet_transactions_with_filter(
+        &self,
+        filter: TransactionFilter,
+    ) -> ApiResult<Vec<CryptoWalletTransaction>> {
+        self.block_on(
+            self.inner
+                .get_crypto_wallet_transactions_with_filter(filter),
+        )
+    }
+
+    /// Get fiat wallet transactions
+    /// Requires APIKEY
+    pub fn get_fiat_wallet_transactions(&self) -> ApiResult<Vec<FiatWalletTransaction>> {
+        self.block_on(self.inner.get_fiat_wallet_transactions())
+    }
+
+    /// Get fiat wallet transactions
+    /// if specified, get transactions with provided filters
+    /// Requires APIKEY
+    pub fn get_fiat_wallet_transactions_ex(
+        &self,
+        transaction_type: Option<TransactionType>,
+        status: Option<TransactionStatus>,
+        max_results: Option<usize>,
+    ) -> ApiResult<Vec<FiatWalletTransaction>> {
+        self.block_on(self.inner.get_fiat_wallet_transactions_ex(
+            transaction_type,
+            status,
+            max_results,
+        ))
+    }
+
+    /// Get fiat wallet transaction with the provided id.
+    /// Returns `ApiError::NotFound` if there is no such transaction
+    /// Requires APIKEY
+    pub fn get_fiat_wallet_transaction(&self, id: &str) -> ApiResult<FiatWalletTransaction> {
+        self.block_on(self.inner.get_fiat_wallet_transaction(id))
+    }
+
+    /// Get fiat wallet transactions matching `filter`
+    /// Requires APIKEY
+    pub fn get_fiat_wallet_transactions_with_filter(
+        &self,
+        filter: TransactionFilter,
+    ) -> ApiResult<Vec<FiatWalletTransaction>> {
+        self.block_on(self.inner.get_fiat_wallet_transactions_with_filter(filter))
+    }
+
+    /// Get assets available on Bitpanda by class
+    pub fn get_assets(&self, asset_class: AssetClass) -> ApiResult<Vec<Asset>> {
+        self.block_on(self.inner.get_assets(asset_class))
+    }
+
+    /// get OHLC for provided symbols
+    pub fn get_ohlc(
+        &self,
+        period: Period,
+        pid: &str,
+        currency: &str,
+    ) -> ApiResult<OpenHighLowCloseChart> {
+        self.block_on(self.inner.get_ohlc(period, pid, currency))
+    }
+
+    /// Get the current prices of all the assets in all the supported fiat currencies
+    pub fn get_ticker(&self) -> ApiResult<Ticker> {
+        self.block_on(self.inner.get_ticker())
+    }
+
+    /// Get a view of the client returning the raw attributes of each resource along with it
+    pub fn with_raw(&self) -> RawClient<'_> {
+        RawClient { client: self }
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+}
+
+/// Blocking view of the client which returns each resource along with the raw JSON:API `attributes` object
+/// it was parsed from. See [`crate::RawClient`].
+///
+/// Get it with [`Client::with_raw`]
+#[derive(Clone, Copy)]
+pub struct RawClient<'a> {
+    client: &'a Client,
+}
+
+impl RawClient<'_> {
+    /// Get asset wallets for user.
+    /// Requires APIKEY
+    pub fn get_asset_wallets(&self) -> ApiResult<Vec<(AssetWallet, Value)>> {
+        self.client
+            .block_on(self.client.inner.with_raw().get_asset_wallets())
+    }
+
+    /// Get crypto wallets
+    /// Requires APIKEY
+    pub fn get_crypto_wallets(&self) -> ApiResult<Vec<(CryptoWallet, Value)>> {
+        self.client
+            .block_on(self.client.inner.with_raw().get_crypto_wallets())
+    }
+
+    /// Get FIAT wallets
+    /// Requires APIKEY
+    pub fn get_fiat_wallets(&self) -> ApiResult<Vec<(FiatWallet, Value)>> {
+        self.client
+            .block_on(self.client.inner.with_raw().get_fiat_wallets())
+    }
+
+    /// get user's trades matching `filter`.
+    /// Requires APIKEY
+    pub fn get_trades(&self, filter: TradeFilter) -> ApiResult<Vec<(Trade, Value)>> {
+        self.client
+            .block_on(self.client.inner.with_raw().get_trades(filter))
+    }
+
+    /// get user's trade with the provided id.
+    /// Returns `ApiError::NotFound` if there is no such trade
+    /// Requires APIKEY
+    pub fn get_trade(&self, id: &str) -> ApiResult<(Trade, Value)> {
+        self.client
+            .block_on(self.client.inner.with_raw().get_trade(id))
+    }
+
+    /// Get crypto wallet transactions matching `filter`
+    /// Requires APIKEY
+    pub fn get_crypto_wallet_transactions(
+        &self,
+        filter: TransactionFilter,
+    ) -> ApiResult<Vec<(CryptoWalletTransaction, Value)>> {
+        self.client.block_on(
+            self.client
+                .inner
+                .with_raw()
+                .get_crypto_wallet_transactions(filter),
+        )
+    }
+
+    /// Get crypto wallet transaction with the provided id.
+    /// Returns `ApiError::NotFound` if there is no such transaction
+    /// Requires APIKEY
+    pub fn get_crypto_wallet_transaction(
+        &self,
+        id: &str,
+    ) -> ApiResult<(CryptoWalletTransaction, Value)> {
+        self.client.block_on(
+            self.client
+                .inner
+                .with_raw()
+                .get_crypto_wallet_transaction(id),
+        )
+    }
+
+    /// Get fiat wallet transactions matching `filter`
+    /// Requires APIKEY
+    pub fn get_fiat_wallet_transactions(
+        &self,
+        filter: TransactionFilter,
+    ) -> ApiResult<Vec<(FiatWalletTransaction, Value)>> {
+        self.client.block_on(
+            self.client
+                .inner
+                .with_raw()
+                .get_fiat_wallet_transactions(filter),
+        )
+    }
+
+    /// Get fiat wallet transaction with the provided id.
+    /// Returns `ApiError::NotFound` if there is no such transaction
+    /// Requires APIKEY
+    pub fn get_fiat_wallet_transaction(
+        &self,
+        id: &str,
+    ) -> ApiResult<(FiatWalletTransaction, Value)> {
+        self.client
+            .block_on(self.client.inner.with_raw().get_fiat_wallet_transaction(id))
+    }
+
+    /// Get assets available on Bitpanda by class
+    pub fn get_assets(&self, asset_class: AssetClass) -> ApiResult<Vec<(Asset, Value)>> {
+        self.client
+            .block_on(self.client.inner.with_raw().get_assets(asset_class))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::{fixture, MockTransport};
+    use crate::ApiError;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_get_trades_blocking() {
+        let transport = MockTransport::new();
+        transport.set_trades(fixture::trades(40));
+        let client = Client::new(transport.client());
+        assert_eq!(client.get_trades().unwrap(), fixture::trades(40));
+        assert_eq!(client.get_trades_ex(Some(10)).unwrap().len(), 10);
+        let trade = fixture::trades(1).remove(0);
+        assert_eq!(client.get_trade(&trade.id).unwrap(), trade);
+        let (raw_trade, attributes) = client.with_raw().get_trade(&trade.id).unwrap();
+        assert_eq!(raw_trade, trade);
+        assert_eq!(attributes["cryptocoin_symbol"], trade.symbol.as_str());
+    }
+
+    #[test]
+    fn should_get_transactions_blocking() {
+        let transport = MockTransport::new();
+        transport.set_crypto_wallet_transactions(fixture::crypto_wallet_transactions(20));
+        transport.set_fiat_wallet_transactions(fixture::fiat_wallet_transactions(20));
+        let client = Client::from(transport.client());
+        assert_eq!(
+            client.get_crypto_wallet_transactions().unwrap(),
+            fixture::crypto_wallet_transactions(20)
+        );
+        assert_eq!(
+            client
+                .get_fiat_wallet_transactions_ex(None, Some(TransactionStatus::Canceled), None)
+                .unwrap()
+                .len(),
+            5
+        );
+    }
+
+    #[test]
+    fn should_fail_blocking_without_api_key() {
+        let client = Client::new(Client::builder().transport(MockTransport::new()).build());
+        assert!(matches!(
+            client.get_asset_wallets().unwrap_err(),
+            ApiError::Unauthorized(_)
+        ));
+    }
+}
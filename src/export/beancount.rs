@@ -0,0 +1,269 @@
+//! # Beancount
+//!
+//! Render a [`Journal`] in the [beancount](https://beancount.github.io) format.
+//!
+//! Crypto assets are held at cost: buys and deposits create lots, while sells and withdrawals reduce them
+//! with an empty cost spec (`{}`), leaving the lot selection to the booking method of the ledger.
+//! The realized gains are balanced on the gains account. Each transaction carries the Bitpanda id
+//! as `bitpanda_id` metadata, so that re-exported entries can be deduplicated.
+
+use std::collections::BTreeMap;
+use std::io;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use super::journal::{decimal, Direction, Entry, EntryKind, Journal, Transfer};
+use super::ExportResult;
+use crate::model::TradeType;
+
+/// Commodity every unit value is expressed in
+const EUR: &str = "EUR";
+
+/// Write the journal to `w` in the beancount format.
+/// The accounts are opened on the date of their first entry
+pub fn write<W: io::Write>(journal: &Journal, mut w: W) -> ExportResult<()> {
+    let entries: Vec<(&Entry, Vec<Posting>)> = journal
+        .entries
+        .iter()
+        .map(|entry| (entry, postings(journal, &entry.kind)))
+        .collect();
+
+    let mut opened: BTreeMap<&str, NaiveDate> = BTreeMap::new();
+    for (entry, postings) in &entries {
+        for posting in postings {
+            opened
+                .entry(posting.account.as_str())
+                .or_insert_with(|| entry.datetime.date_naive());
+        }
+    }
+    for (account, date) in &opened {
+        writeln!(w, "{date} open {account}")?;
+    }
+
+    for (entry, postings) in entries {
+        let date = entry.datetime.format("%Y-%m-%d");
+        writeln!(w)?;
+        if let EntryKind::Trade(legs) = &entry.kind {
+            for leg in legs {
+                writeln!(
+                    w,
+                    "{date} price {} {}",
+                    commodity(&leg.asset),
+                    amount(leg.price, &leg.fiat)
+                )?;
+            }
+        }
+        writeln!(w, "{date} * \"Bitpanda\" \"{}\"", entry.narration)?;
+        writeln!(w, "  bitpanda_id: \"{}\"", entry.id)?;
+        for posting in postings {
+            match posting.units {
+                Some(units) => writeln!(w, "  {}  {units}", posting.account)?,
+                None => writeln!(w, "  {}", posting.account)?,
+            }
+        }
+    }
+
+    w.flush().map_err(Into::into)
+}
+
+/// A posting; postings without units are balanced automatically
+struct Posting {
+    account: String,
+    units: Option<String>,
+}
+
+impl Posting {
+    fn new(account: String, units: String) -> Self {
+        Self {
+            account,
+            units: Some(units),
+        }
+    }
+
+    fn balancing(account: String) -> Self {
+        Self {
+            account,
+            units: None,
+        }
+    }
+}
+
+fn postings(journal: &Journal, kind: &EntryKind) -> Vec<Posting> {
+    let accounts = &journal.accounts;
+    match kind {
+        EntryKind::Trade(legs) => {
+            let mut postings = Vec::new();
+            for leg in legs {
+                let asset_account = accounts.asset(&commodity(&leg.asset));
+                let fiat_account = accounts.asset(&commodity(&leg.fiat));
+                match leg.r#type {
+                    TradeType::Buy => {
+                        postings.push(Posting::new(
+                            asset_account,
+                            format!(
+                                "{} {{{{{}}}}}",
+                                amount(leg.amount, &leg.asset),
+                                amount(leg.fiat_amount, &leg.fiat)
+                            ),
+                        ));
+                        postings.push(Posting::new(
+                            fiat_account,
+                            amount(-leg.fiat_amount, &leg.fiat),
+                        ));
+                    }
+                    TradeType::Sell => {
+                        postings.push(Posting::new(
+                            asset_account,
+                            format!(
+                                "{} {{}} @ {}",
+                                amount(-leg.amount, &leg.asset),
+                                amount(leg.price, &leg.fiat)
+                            ),
+                        ));
+                        postings.push(Posting::new(
+                            fiat_account,
+                            amount(leg.fiat_amount, &leg.fiat),
+                        ));
+                    }
+                }
+            }
+            if legs.iter().any(|leg| leg.r#type == TradeType::Sell) {
+                postings.push(Posting::balancing(accounts.gains.clone()));
+            }
+            postings
+        }
+        EntryKind::Transfer(Transfer {
+            direction,
+            commodity: symbol,
+            amount: transferred,
+            fee,
+            unit_value_eur,
+            ..
+        }) => {
+            let account = accounts.asset(&commodity(symbol));
+            let mut postings = Vec::new();
+            match unit_value_eur {
+                // crypto assets are held at cost
+                Some(unit_value) => {
+                    postings.push(Posting::new(
+                        account,
+                        match direction {
+                            Direction::Incoming => format!(
+                                "{} {{{}}}",
+                                amount(*transferred - *fee, symbol),
+                                amount(*unit_value, EUR)
+                            ),
+                            Direction::Outgoing => {
+                                format!("{} {{}}", amount(-(*transferred + *fee), symbol))
+                            }
+                        },
+                    ));
+                    if !fee.is_zero() {
+                        postings.push(Posting::new(
+                            accounts.fees.clone(),
+                            format!("{} @ {}", amount(*fee, symbol), amount(*unit_value, EUR)),
+                        ));
+                    }
+                    postings.push(Posting::balancing(accounts.external.clone()));
+                }
+                None => {
+                    let (wallet, external) = match direction {
+                        Direction::Incoming => (*transferred - *fee, -*transferred),
+                        Direction::Outgoing => (-(*transferred + *fee), *transferred),
+                    };
+                    postings.push(Posting::new(account, amount(wallet, symbol)));
+                    if !fee.is_zero() {
+                        postings.push(Posting::new(accounts.fees.clone(), amount(*fee, symbol)));
+                    }
+                    postings.push(Posting::new(
+                        accounts.external.clone(),
+                        amount(external, symbol),
+                    ));
+                }
+            }
+            postings
+        }
+    }
+}
+
+fn amount(value: Decimal, symbol: &str) -> String {
+    format!("{} {}", decimal(value), commodity(symbol))
+}
+
+/// Beancount commodities are uppercase and must start with a letter
+fn commodity(symbol: &str) -> String {
+    let symbol: String = symbol
+        .to_ascii_uppercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '\'' | '.' | '_' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if symbol.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        symbol
+    } else {
+        format!("X{symbol}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::export::journal::test::journal;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_write_beancount() {
+        let mut output = Vec::new();
+        write(&journal(), &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"2023-01-10 open Assets:Bitpanda:BTC
+2023-02-01 open Assets:Bitpanda:ETH
+2023-01-02 open Assets:Bitpanda:EUR
+2023-01-02 open Equity:Bitpanda:External
+2023-03-01 open Expenses:Bitpanda:Fees
+2023-02-01 open Income:Bitpanda:Gains
+
+2023-01-02 * "Bitpanda" "Deposit EUR"
+  bitpanda_id: "fiat-tx-1"
+  Assets:Bitpanda:EUR  1000 EUR
+  Equity:Bitpanda:External  -1000 EUR
+
+2023-01-10 price BTC 20000 EUR
+2023-01-10 * "Bitpanda" "Buy BTC"
+  bitpanda_id: "trade-1"
+  Assets:Bitpanda:BTC  0.01 BTC {{200 EUR}}
+  Assets:Bitpanda:EUR  -200 EUR
+
+2023-02-01 price BTC 22000 EUR
+2023-02-01 price ETH 1100 EUR
+2023-02-01 * "Bitpanda" "Swap BTC to ETH"
+  bitpanda_id: "trade-2"
+  Assets:Bitpanda:BTC  -0.005 BTC {} @ 22000 EUR
+  Assets:Bitpanda:EUR  110 EUR
+  Assets:Bitpanda:ETH  0.1 ETH {{110 EUR}}
+  Assets:Bitpanda:EUR  -110 EUR
+  Income:Bitpanda:Gains
+
+2023-03-01 * "Bitpanda" "Withdrawal ETH"
+  bitpanda_id: "crypto-tx-1"
+  Assets:Bitpanda:ETH  -0.051 ETH {}
+  Expenses:Bitpanda:Fees  0.001 ETH @ 1100 EUR
+  Equity:Bitpanda:External
+"#
+        );
+    }
+
+    #[test]
+    fn should_sanitize_commodities() {
+        assert_eq!(commodity("btc"), "BTC");
+        assert_eq!(commodity("1INCH"), "X1INCH");
+    }
+}
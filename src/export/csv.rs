@@ -0,0 +1,373 @@
+//! # CSV
+//!
+//! CSV writers for the Bitpanda models.
+//!
+//! Each model has a stable column layout, which is written as header of the file:
+//!
+//! | Model                     | Columns |
+//! |---------------------------|---------|
+//! | [`Trade`]                 | `id`, `datetime`, `type`, `status`, `symbol`, `amount_asset`, `amount_fiat`, `price`, `fiat_to_eur_rate`, `id_asset`, `id_fiat`, `id_wallet`, `fiat_wallet_id`, `related_swap_trade_id` |
+//! | [`CryptoWalletTransaction`] | `id`, `datetime`, `type`, `status`, `in_or_out`, `cryptocoin_id`, `amount`, `amount_eur`, `fee`, `current_fiat_id`, `current_fiat_amount`, `confirmations`, `recipient`, `wallet_id` |
+//! | [`FiatWalletTransaction`] | `id`, `datetime`, `type`, `status`, `in_or_out`, `fiat_id`, `amount`, `fee`, `to_eur_rate`, `user_id`, `wallet_id` |
+//! | [`AssetWallet`]           | `id`, `name`, `class`, `asset_id`, `asset_symbol`, `balance`, `is_default`, `deleted` |
+//! | [`Ohlc`]                  | `time`, `open`, `high`, `low`, `close` |
+//!
+//! Times are formatted as RFC3339, enums with the Bitpanda names (e.g. `buy`, `finished`, `incoming`),
+//! asset classes as `commodity`, `cryptocurrency`, `cryptoindex`, `etf`, `metal` and `stock`,
+//! and missing values as empty fields.
+
+use std::io;
+use std::marker::PhantomData;
+
+use rust_decimal::Decimal;
+
+use super::ExportResult;
+use crate::model::crypto_wallet::CryptoWalletTransaction;
+use crate::model::fiat_wallet::FiatWalletTransaction;
+use crate::model::ohlc::Ohlc;
+use crate::model::{AssetWallet, Trade};
+
+/// CSV formatting options
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CsvOptions {
+    delimiter: u8,
+    decimal_separator: char,
+    header: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            decimal_separator: '.',
+            header: true,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Set the field delimiter (default: `,`)
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+
+        self
+    }
+
+    /// Set the decimal separator used to format numbers (default: `.`)
+    pub fn decimal_separator(mut self, separator: char) -> Self {
+        self.decimal_separator = separator;
+
+        self
+    }
+
+    /// Set whether to write the header (default: true)
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = header;
+
+        self
+    }
+
+    /// Format a decimal with the configured separator
+    pub(crate) fn decimal(&self, value: Decimal) -> String {
+        let value = value.normalize().to_string();
+        if self.decimal_separator == '.' {
+            value
+        } else {
+            value.replace('.', &self.decimal_separator.to_string())
+        }
+    }
+}
+
+/// A model which can be written as a CSV record
+pub trait CsvRecord {
+    /// Names of the columns
+    fn header() -> &'static [&'static str];
+
+    /// Fields of the record, in the same order of the header
+    fn fields(&self, options: &CsvOptions) -> Vec<String>;
+}
+
+/// Streaming CSV writer for the records of type `T`
+pub struct CsvWriter<W: io::Write, T: CsvRecord> {
+    writer: ::csv::Writer<W>,
+    options: CsvOptions,
+    header_written: bool,
+    record: PhantomData<T>,
+}
+
+impl<W: io::Write, T: CsvRecord> CsvWriter<W, T> {
+    /// Create a writer writing to `writer` with `options`
+    pub fn new(writer: W, options: CsvOptions) -> Self {
+        Self {
+            writer: ::csv::WriterBuilder::new()
+                .delimiter(options.delimiter)
+                .from_writer(writer),
+            header_written: !options.header,
+            options,
+            record: PhantomData,
+        }
+    }
+
+    /// Write a record. The header is written before the first record
+    pub fn write(&mut self, record: &T) -> ExportResult<()> {
+        self.write_header()?;
+        self.writer.write_record(record.fields(&self.options))?;
+
+        Ok(())
+    }
+
+    /// Write all the provided records
+    pub fn write_all<'a>(&mut self, records: impl IntoIterator<Item = &'a T>) -> ExportResult<()>
+    where
+        T: 'a,
+    {
+        for record in records {
+            self.write(record)?;
+        }
+
+        Ok(())
+    }
+
+    /// Flush the writer and get the underlying writer back.
+    /// The header is written if no record has been written
+    pub fn into_inner(mut self) -> ExportResult<W> {
+        self.write_header()?;
+        self.writer
+            .into_inner()
+            .map_err(|err| err.into_error().into())
+    }
+
+    /// Flush the writer
+    pub fn flush(&mut self) -> ExportResult<()> {
+        self.writer.flush()?;
+
+        Ok(())
+    }
+
+    fn write_header(&mut self) -> ExportResult<()> {
+        if !self.header_written {
+            self.writer.write_record(T::header())?;
+            self.header_written = true;
+        }
+
+        Ok(())
+    }
+}
+
+impl CsvRecord for Trade {
+    fn header() -> &'static [&'static str] {
+        &[
+            "id",
+            "datetime",
+            "type",
+            "status",
+            "symbol",
+            "amount_asset",
+            "amount_fiat",
+            "price",
+            "fiat_to_eur_rate",
+            "id_asset",
+            "id_fiat",
+            "id_wallet",
+            "fiat_wallet_id",
+            "related_swap_trade_id",
+        ]
+    }
+
+    fn fields(&self, options: &CsvOptions) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.datetime.to_rfc3339(),
+            self.r#type.to_string(),
+            self.status.to_string(),
+            self.symbol.clone(),
+            options.decimal(self.amount_asset),
+            options.decimal(self.amount_fiat),
+            options.decimal(self.price),
+            options.decimal(self.fiat_to_eur_rate),
+            self.id_asset.clone(),
+            self.id_fiat.clone(),
+            self.id_wallet.clone(),
+            self.fiat_wallet_id.clone().unwrap_or_default(),
+            self.related_swap_trade
+                .as_ref()
+                .map(|trade| trade.id.clone())
+                .unwrap_or_default(),
+        ]
+    }
+}
+
+impl CsvRecord for CryptoWalletTransaction {
+    fn header() -> &'static [&'static str] {
+        &[
+            "id",
+            "datetime",
+            "type",
+            "status",
+            "in_or_out",
+            "cryptocoin_id",
+            "amount",
+            "amount_eur",
+            "fee",
+            "current_fiat_id",
+            "current_fiat_amount",
+            "confirmations",
+            "recipient",
+            "wallet_id",
+        ]
+    }
+
+    fn fields(&self, options: &CsvOptions) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.datetime.to_rfc3339(),
+            self.transaction_type.to_string(),
+            self.status.to_string(),
+            self.in_or_out.to_string(),
+            self.cryptocoin_id.clone(),
+            options.decimal(self.amount),
+            options.decimal(self.amount_eur),
+            options.decimal(self.fee),
+            self.current_fiat_id.clone(),
+            options.decimal(self.current_fiat_amount),
+            self.confirmations.to_string(),
+            self.recipient.clone(),
+            self.wallet_id.clone(),
+        ]
+    }
+}
+
+impl CsvRecord for FiatWalletTransaction {
+    fn header() -> &'static [&'static str] {
+        &[
+            "id",
+            "datetime",
+            "type",
+            "status",
+            "in_or_out",
+            "fiat_id",
+            "amount",
+            "fee",
+            "to_eur_rate",
+            "user_id",
+            "wallet_id",
+        ]
+    }
+
+    fn fields(&self, options: &CsvOptions) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.datetime.to_rfc3339(),
+            self.transaction_type.to_string(),
+            self.status.to_string(),
+            self.in_or_out.to_string(),
+            self.fiat_id.clone(),
+            options.decimal(self.amount),
+            options.decimal(self.fee),
+            options.decimal(self.to_eur_rate),
+            self.user_id.clone(),
+            self.wallet_id.clone(),
+        ]
+    }
+}
+
+impl CsvRecord for AssetWallet {
+    fn header() -> &'static [&'static str] {
+        &[
+            "id",
+            "name",
+            "class",
+            "asset_id",
+            "asset_symbol",
+            "balance",
+            "is_default",
+            "deleted",
+        ]
+    }
+
+    fn fields(&self, options: &CsvOptions) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.name.clone(),
+            self.class.as_str().to_string(),
+            self.asset_id.clone(),
+            self.asset_symbol.clone(),
+            options.decimal(self.balance),
+            self.is_default.to_string(),
+            self.deleted.to_string(),
+        ]
+    }
+}
+
+impl CsvRecord for Ohlc {
+    fn header() -> &'static [&'static str] {
+        &["time", "open", "high", "low", "close"]
+    }
+
+    fn fields(&self, options: &CsvOptions) -> Vec<String> {
+        vec![
+            self.time.to_rfc3339(),
+            options.decimal(self.open),
+            options.decimal(self.high),
+            options.decimal(self.low),
+            options.decimal(self.close),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::Trade;
+    use crate::testing::fixture::{datetime, TradeBuilder};
+
+    use pretty_assertions::assert_eq;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn should_write_trades() {
+        let mut writer = CsvWriter::new(Vec::new(), CsvOptions::default());
+        writer.write_all(&[trade()]).unwrap();
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+        assert_eq!(
+            csv,
+            "id,datetime,type,status,symbol,amount_asset,amount_fiat,price,fiat_to_eur_rate,id_asset,id_fiat,id_wallet,fiat_wallet_id,related_swap_trade_id\n\
+             trade-1,2023-01-03T10:15:00+01:00,buy,finished,BTC,0.004,100,25000,1,1,1,wallet-btc,fiat-wallet-eur,\n"
+        );
+    }
+
+    #[test]
+    fn should_write_with_custom_format() {
+        let mut writer = CsvWriter::new(
+            Vec::new(),
+            CsvOptions::default()
+                .delimiter(b';')
+                .decimal_separator(',')
+                .header(false),
+        );
+        writer.write(&trade()).unwrap();
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+        assert_eq!(
+            csv,
+            "trade-1;2023-01-03T10:15:00+01:00;buy;finished;BTC;0,004;100;25000;1;1;1;wallet-btc;fiat-wallet-eur;\n"
+        );
+    }
+
+    #[test]
+    fn should_write_header_only() {
+        let writer: CsvWriter<Vec<u8>, Ohlc> = CsvWriter::new(Vec::new(), CsvOptions::default());
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+        assert_eq!(csv, "time,open,high,low,close\n");
+    }
+
+    fn trade() -> Trade {
+        TradeBuilder::new("trade-1")
+            .datetime(datetime("2023-01-03T10:15:00+01:00"))
+            .amounts(dec!(0.004), dec!(100))
+            .build()
+    }
+}
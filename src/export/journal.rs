@@ -0,0 +1,549 @@
+//! # Journal
+//!
+//! Accounting journal built out of the user's history, which can be rendered as a plain-text accounting
+//! ledger with [`super::ledger`] or [`super::beancount`], or imported by the tax tools with [`super::tax_tool`].
+//!
+//! Only finished trades and transactions are journaled, and swaps only once both their legs are finished. Wallet transactions of type buy and sell are skipped,
+//! since they are already represented by the trades, while the other transactions are journaled as transfers
+//! from or to an external account.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, FixedOffset};
+use rust_decimal::Decimal;
+
+use crate::model::crypto_wallet::CryptoWalletTransaction;
+use crate::model::fiat_wallet::FiatWalletTransaction;
+use crate::model::{
+    CryptoWallet, FiatWallet, InOrOut, Trade, TradeStatus, TradeType, TransactionStatus,
+    TransactionType,
+};
+
+/// Decimal places of the unit values computed from the transactions
+const UNIT_VALUE_SCALE: u32 = 8;
+
+/// Accounts used by the journal
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Accounts {
+    /// Parent account of the Bitpanda wallets; each commodity has its own sub-account
+    pub assets: String,
+    /// Account for the fees
+    pub fees: String,
+    /// Account for the realized capital gains
+    pub gains: String,
+    /// Account assets are transferred from and to
+    pub external: String,
+}
+
+impl Default for Accounts {
+    fn default() -> Self {
+        Self {
+            assets: "Assets:Bitpanda".to_string(),
+            fees: "Expenses:Bitpanda:Fees".to_string(),
+            gains: "Income:Bitpanda:Gains".to_string(),
+            external: "Equity:Bitpanda:External".to_string(),
+        }
+    }
+}
+
+impl Accounts {
+    /// Account of the wallet for `commodity`
+    pub fn asset(&self, commodity: &str) -> String {
+        format!("{}:{commodity}", self.assets)
+    }
+}
+
+/// An entry of the journal
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Entry {
+    /// Bitpanda id of the trade or transaction, used to tag the entry; swaps are tagged with the id of their sell leg
+    pub id: String,
+    pub datetime: DateTime<FixedOffset>,
+    pub narration: String,
+    pub kind: EntryKind,
+}
+
+/// Describes what an entry records
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum EntryKind {
+    /// A trade; swaps have two legs, one sell and one buy
+    Trade(Vec<TradeLeg>),
+    /// A transfer from or to the external account
+    Transfer(Transfer),
+}
+
+/// A buy or a sell of an asset against a fiat currency
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TradeLeg {
+    pub r#type: TradeType,
+    /// Commodity of the asset
+    pub asset: String,
+    pub amount: Decimal,
+    /// Commodity of the fiat currency
+    pub fiat: String,
+    pub fiat_amount: Decimal,
+    /// Price of a unit of the asset in the fiat currency
+    pub price: Decimal,
+}
+
+/// Direction of a transfer
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Direction {
+    /// The commodity was received
+    Incoming,
+    /// The commodity was sent
+    Outgoing,
+}
+
+/// A deposit or a withdrawal of a commodity
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Transfer {
+    pub transaction_type: TransactionType,
+    pub direction: Direction,
+    pub commodity: String,
+    /// Amount transferred
+    pub amount: Decimal,
+    /// Fee paid in the same commodity
+    pub fee: Decimal,
+    /// Value in EUR of a unit of the commodity for crypto assets, which are held at cost; `None` for fiat currencies
+    pub unit_value_eur: Option<Decimal>,
+}
+
+/// Accounting journal of the user's history
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Journal {
+    pub accounts: Accounts,
+    /// Entries ordered by time
+    pub entries: Vec<Entry>,
+}
+
+impl Journal {
+    /// Get a journal builder
+    pub fn builder<'a>() -> JournalBuilder<'a> {
+        JournalBuilder::default()
+    }
+}
+
+/// Journal builder
+#[derive(Default)]
+pub struct JournalBuilder<'a> {
+    accounts: Accounts,
+    trades: &'a [Trade],
+    crypto_transactions: &'a [CryptoWalletTransaction],
+    fiat_transactions: &'a [FiatWalletTransaction],
+    symbols: HashMap<String, String>,
+}
+
+impl<'a> JournalBuilder<'a> {
+    /// Set the accounts to use
+    pub fn accounts(mut self, accounts: Accounts) -> Self {
+        self.accounts = accounts;
+
+        self
+    }
+
+    /// Trades to journal
+    pub fn trades(mut self, trades: &'a [Trade]) -> Self {
+        self.trades = trades;
+
+        self
+    }
+
+    /// Crypto wallet transactions to journal
+    pub fn crypto_transactions(mut self, transactions: &'a [CryptoWalletTransaction]) -> Self {
+        self.crypto_transactions = transactions;
+
+        self
+    }
+
+    /// Fiat wallet transactions to journal
+    pub fn fiat_transactions(mut self, transactions: &'a [FiatWalletTransaction]) -> Self {
+        self.fiat_transactions = transactions;
+
+        self
+    }
+
+    /// Resolve the symbols of the cryptocoin ids from the crypto wallets
+    pub fn crypto_wallets(mut self, wallets: &[CryptoWallet]) -> Self {
+        self.symbols.extend(
+            wallets
+                .iter()
+                .map(|wallet| (wallet.cryptocoin_id.clone(), wallet.symbol.clone())),
+        );
+
+        self
+    }
+
+    /// Resolve the symbols of the fiat ids from the fiat wallets.
+    /// Fiat ids which can't be resolved are journaled as `FIAT<id>`
+    pub fn fiat_wallets(mut self, wallets: &[FiatWallet]) -> Self {
+        self.symbols.extend(
+            wallets
+                .iter()
+                .map(|wallet| (fiat_key(&wallet.fiat_id), wallet.symbol.clone())),
+        );
+
+        self
+    }
+
+    /// Build the journal
+    pub fn build(mut self) -> Journal {
+        // cryptocoin ids can also be resolved from the trades
+        for trade in self.trades.iter().chain(
+            self.trades
+                .iter()
+                .filter_map(|trade| trade.related_swap_trade.as_deref()),
+        ) {
+            self.symbols
+                .entry(trade.id_asset.clone())
+                .or_insert_with(|| trade.symbol.clone());
+        }
+
+        // the legs of a swap may reference each other or only one way, in any order
+        let by_id: HashMap<&str, &Trade> = self
+            .trades
+            .iter()
+            .map(|trade| (trade.id.as_str(), trade))
+            .collect();
+        let mut swap_legs: HashMap<&str, &Trade> = HashMap::new();
+        for trade in self.trades.iter() {
+            if let Some(related) = trade.related_swap_trade.as_deref() {
+                let related = by_id.get(related.id.as_str()).copied().unwrap_or(related);
+                swap_legs.entry(trade.id.as_str()).or_insert(related);
+                swap_legs.entry(related.id.as_str()).or_insert(trade);
+            }
+        }
+
+        let mut entries = Vec::new();
+        let mut journaled = HashSet::new();
+        for trade in self.trades.iter() {
+            if !journaled.insert(trade.id.as_str()) {
+                continue;
+            }
+            let mut legs = vec![trade];
+            if let Some(related) = swap_legs.get(trade.id.as_str()) {
+                journaled.insert(related.id.as_str());
+                legs.push(related);
+                // the sell leg comes first and tags the entry, whatever the order of the trades
+                legs.sort_by(|a, b| {
+                    (a.r#type == TradeType::Buy)
+                        .cmp(&(b.r#type == TradeType::Buy))
+                        .then_with(|| a.id.cmp(&b.id))
+                });
+            }
+            if let Some(leg) = legs.iter().find(|leg| leg.status != TradeStatus::Finished) {
+                if legs.len() > 1 {
+                    debug!(
+                        "skipping swap {} with unfinished leg {}",
+                        legs[0].id, leg.id
+                    );
+                }
+                continue;
+            }
+            let trade = legs[0];
+            let narration = match legs.as_slice() {
+                [sell, buy] => format!("Swap {} to {}", sell.symbol, buy.symbol),
+                _ => format!(
+                    "{} {}",
+                    match trade.r#type {
+                        TradeType::Buy => "Buy",
+                        TradeType::Sell => "Sell",
+                    },
+                    trade.symbol
+                ),
+            };
+            entries.push(Entry {
+                id: trade.id.clone(),
+                datetime: trade.datetime,
+                narration,
+                kind: EntryKind::Trade(
+                    legs.into_iter()
+                        .map(|leg| TradeLeg {
+                            r#type: leg.r#type,
+                            asset: leg.symbol.clone(),
+                            amount: leg.amount_asset,
+                            fiat: self.fiat_symbol(&leg.id_fiat),
+                            fiat_amount: leg.amount_fiat,
+                            price: leg.price,
+                        })
+                        .collect(),
+                ),
+            });
+        }
+
+        for tx in self.crypto_transactions.iter().filter(|tx| {
+            tx.status == TransactionStatus::Finished && is_transfer(&tx.transaction_type)
+        }) {
+            let Some(direction) = direction(&tx.id, &tx.in_or_out) else {
+                continue;
+            };
+            let commodity = self
+                .symbols
+                .get(&tx.cryptocoin_id)
+                .cloned()
+                .unwrap_or_else(|| format!("CRYPTO{}", tx.cryptocoin_id));
+            entries.push(Entry {
+                id: tx.id.clone(),
+                datetime: tx.datetime,
+                narration: transfer_narration(&tx.transaction_type, &commodity),
+                kind: EntryKind::Transfer(Transfer {
+                    transaction_type: tx.transaction_type.clone(),
+                    direction,
+                    commodity,
+                    amount: tx.amount,
+                    fee: tx.fee,
+                    unit_value_eur: Some(if tx.amount.is_zero() {
+                        Decimal::ZERO
+                    } else {
+                        (tx.amount_eur / tx.amount).round_dp(UNIT_VALUE_SCALE)
+                    }),
+                }),
+            });
+        }
+
+        for tx in self.fiat_transactions.iter().filter(|tx| {
+            tx.status == TransactionStatus::Finished && is_transfer(&tx.transaction_type)
+        }) {
+            let Some(direction) = direction(&tx.id, &tx.in_or_out) else {
+                continue;
+            };
+            let commodity = self.fiat_symbol(&tx.fiat_id);
+            entries.push(Entry {
+                id: tx.id.clone(),
+                datetime: tx.datetime,
+                narration: transfer_narration(&tx.transaction_type, &commodity),
+                kind: EntryKind::Transfer(Transfer {
+                    transaction_type: tx.transaction_type.clone(),
+                    direction,
+                    commodity,
+                    amount: tx.amount,
+                    fee: tx.fee,
+                    unit_value_eur: None,
+                }),
+            });
+        }
+
+        entries.sort_by_key(|entry| entry.datetime);
+
+        Journal {
+            accounts: self.accounts,
+            entries,
+        }
+    }
+
+    fn fiat_symbol(&self, fiat_id: &str) -> String {
+        self.symbols
+            .get(&fiat_key(fiat_id))
+            .cloned()
+            .unwrap_or_else(|| format!("FIAT{fiat_id}"))
+    }
+}
+
+/// Format a decimal without trailing zeros
+pub(crate) fn decimal(value: Decimal) -> String {
+    value.normalize().to_string()
+}
+
+/// Fiat and cryptocoin ids may overlap, so fiat ids are stored with a prefix
+fn fiat_key(fiat_id: &str) -> String {
+    format!("fiat:{fiat_id}")
+}
+
+fn is_transfer(transaction_type: &TransactionType) -> bool {
+    !matches!(
+        transaction_type,
+        TransactionType::Buy | TransactionType::Sell
+    )
+}
+
+/// Direction of the transaction; `None` if it's unknown, since the transaction can't be journaled
+fn direction(id: &str, in_or_out: &InOrOut) -> Option<Direction> {
+    match in_or_out {
+        InOrOut::Incoming => Some(Direction::Incoming),
+        InOrOut::Outgoing => Some(Direction::Outgoing),
+        InOrOut::Unknown(direction) => {
+            debug!("skipping transaction {id} with unknown direction {direction}");
+            None
+        }
+    }
+}
+
+fn transfer_narration(transaction_type: &TransactionType, commodity: &str) -> String {
+    let kind = match transaction_type {
+        TransactionType::Deposit => "Deposit",
+        TransactionType::Ico => "ICO",
+        TransactionType::Refund => "Refund",
+        TransactionType::Transfer => "Transfer",
+        TransactionType::Withdrawal => "Withdrawal",
+        TransactionType::Buy => "Buy",
+        TransactionType::Sell => "Sell",
+        TransactionType::Unknown(s) => s,
+    };
+    format!("{kind} {commodity}")
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+
+    use std::slice;
+
+    use crate::testing::fixture::{datetime, FiatWalletTransactionBuilder, TradeBuilder};
+
+    use pretty_assertions::assert_eq;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn should_build_journal() {
+        let journal = journal();
+
+        assert_eq!(
+            journal
+                .entries
+                .iter()
+                .map(|entry| (entry.id.as_str(), entry.narration.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("fiat-tx-1", "Deposit EUR"),
+                ("trade-1", "Buy BTC"),
+                ("trade-2", "Swap BTC to ETH"),
+                ("crypto-tx-1", "Withdrawal ETH"),
+            ]
+        );
+        let EntryKind::Trade(legs) = &journal.entries[2].kind else {
+            panic!("not a trade");
+        };
+        assert_eq!(legs[0].r#type, TradeType::Sell);
+        assert_eq!(legs[1].r#type, TradeType::Buy);
+        assert_eq!(legs[1].fiat, "EUR");
+    }
+
+    #[test]
+    fn should_journal_swaps_whatever_the_order_of_the_legs() {
+        let swap_buy = TradeBuilder::new("trade-1")
+            .asset("5", "ETH")
+            .amounts(dec!(0.1), dec!(110))
+            .build();
+        let swap_sell = TradeBuilder::new("trade-2")
+            .r#type(TradeType::Sell)
+            .swap(swap_buy.clone())
+            .build();
+        // the buy leg doesn't reference the sell leg
+        let sell_first = Journal::builder()
+            .trades(&[swap_sell.clone(), swap_buy.clone()])
+            .build();
+        let buy_first = Journal::builder().trades(&[swap_buy, swap_sell]).build();
+
+        assert_eq!(sell_first, buy_first);
+        assert_eq!(buy_first.entries.len(), 1);
+        assert_eq!(buy_first.entries[0].id, "trade-2");
+        assert_eq!(buy_first.entries[0].narration, "Swap BTC to ETH");
+    }
+
+    #[test]
+    fn should_skip_swaps_with_unfinished_legs() {
+        let pending_buy = TradeBuilder::new("trade-1")
+            .status(TradeStatus::Pending)
+            .asset("5", "ETH")
+            .build();
+        let sell = TradeBuilder::new("trade-2")
+            .r#type(TradeType::Sell)
+            .swap(pending_buy.clone())
+            .build();
+        let journal = Journal::builder().trades(slice::from_ref(&sell)).build();
+        assert!(journal.entries.is_empty());
+
+        // the status of the leg listed on its own wins over the embedded one
+        let finished_buy = Trade {
+            status: TradeStatus::Finished,
+            ..pending_buy
+        };
+        let journal = Journal::builder().trades(&[sell, finished_buy]).build();
+        assert_eq!(journal.entries.len(), 1);
+        assert_eq!(journal.entries[0].narration, "Swap BTC to ETH");
+    }
+
+    #[test]
+    fn should_skip_transfers_with_unknown_direction() {
+        let transactions = vec![
+            FiatWalletTransactionBuilder::new("fiat-tx-1")
+                .transaction_type(TransactionType::Unknown("reward".to_string()))
+                .build(),
+            FiatWalletTransactionBuilder::new("fiat-tx-2")
+                .in_or_out(InOrOut::Unknown("internal".to_string()))
+                .build(),
+        ];
+        let journal = Journal::builder().fiat_transactions(&transactions).build();
+
+        assert_eq!(journal.entries.len(), 1);
+        assert_eq!(journal.entries[0].id, "fiat-tx-1");
+        assert_eq!(journal.entries[0].narration, "reward FIAT1");
+    }
+
+    /// Deposit 1000 EUR, buy 0.01 BTC for 200 EUR, swap 0.005 BTC for 0.1 ETH, withdraw 0.05 ETH
+    pub(crate) fn journal() -> Journal {
+        let swap_buy = TradeBuilder::new("trade-3")
+            .datetime(datetime("2023-02-01T10:00:00+01:00"))
+            .asset("5", "ETH")
+            .amounts(dec!(0.1), dec!(110))
+            .build();
+        let swap_sell = TradeBuilder::new("trade-2")
+            .r#type(TradeType::Sell)
+            .datetime(datetime("2023-02-01T10:00:00+01:00"))
+            .amounts(dec!(0.005), dec!(110))
+            .swap(swap_buy.clone())
+            .build();
+        let trades = vec![
+            swap_sell,
+            swap_buy,
+            TradeBuilder::new("trade-1")
+                .datetime(datetime("2023-01-10T09:30:00+01:00"))
+                .amounts(dec!(0.01), dec!(200))
+                .build(),
+        ];
+        let crypto_txs = vec![CryptoWalletTransaction {
+            amount_eur: dec!(55),
+            amount: dec!(0.05),
+            confirmations: 10,
+            cryptocoin_id: "5".to_string(),
+            current_fiat_amount: dec!(60),
+            current_fiat_id: "1".to_string(),
+            datetime: "2023-03-01T08:00:00+01:00".parse().unwrap(),
+            fee: dec!(0.001),
+            id: "crypto-tx-1".to_string(),
+            in_or_out: InOrOut::Outgoing,
+            recipient: "0xabc".to_string(),
+            status: TransactionStatus::Finished,
+            transaction_type: TransactionType::Withdrawal,
+            wallet_id: "eth-wallet".to_string(),
+        }];
+        let fiat_txs = vec![FiatWalletTransaction {
+            amount: dec!(1000),
+            datetime: "2023-01-02T12:00:00+01:00".parse().unwrap(),
+            fee: Decimal::ZERO,
+            fiat_id: "1".to_string(),
+            id: "fiat-tx-1".to_string(),
+            in_or_out: InOrOut::Incoming,
+            status: TransactionStatus::Finished,
+            to_eur_rate: Decimal::ONE,
+            transaction_type: TransactionType::Deposit,
+            user_id: "user".to_string(),
+            wallet_id: "eur-wallet".to_string(),
+        }];
+        let fiat_wallets = vec![FiatWallet {
+            balance: dec!(800),
+            fiat_id: "1".to_string(),
+            id: "eur-wallet".to_string(),
+            name: "EUR Wallet".to_string(),
+            pending_transactions_count: 0,
+            symbol: "EUR".to_string(),
+        }];
+
+        Journal::builder()
+            .trades(&trades)
+            .crypto_transactions(&crypto_txs)
+            .fiat_transactions(&fiat_txs)
+            .fiat_wallets(&fiat_wallets)
+            .build()
+    }
+}
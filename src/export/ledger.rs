@@ -0,0 +1,162 @@
+//! # Ledger
+//!
+//! Render a [`Journal`] in the [ledger](https://ledger-cli.org) format, which can be read by hledger too.
+//!
+//! Each wallet has its own account, trades are recorded at their total cost with `@@` and
+//! preceded by a `P` price directive, and every transaction is tagged with its Bitpanda id
+//! (`; bitpanda_id: <id>`), so that re-exported entries can be deduplicated.
+
+use std::io;
+
+use rust_decimal::Decimal;
+
+use super::journal::{decimal, Direction, Entry, EntryKind, Journal, TradeLeg, Transfer};
+use super::ExportResult;
+use crate::model::TradeType;
+
+/// Write the journal to `w` in the ledger format
+pub fn write<W: io::Write>(journal: &Journal, mut w: W) -> ExportResult<()> {
+    for (i, entry) in journal.entries.iter().enumerate() {
+        if i > 0 {
+            writeln!(w)?;
+        }
+        write_entry(journal, entry, &mut w)?;
+    }
+
+    w.flush().map_err(Into::into)
+}
+
+fn write_entry<W: io::Write>(journal: &Journal, entry: &Entry, w: &mut W) -> ExportResult<()> {
+    let date = entry.datetime.format("%Y-%m-%d");
+    if let EntryKind::Trade(legs) = &entry.kind {
+        for leg in legs {
+            writeln!(
+                w,
+                "P {date} {} {}",
+                commodity(&leg.asset),
+                amount(leg.price, &leg.fiat)
+            )?;
+        }
+    }
+    writeln!(w, "{date} * {}", entry.narration)?;
+    writeln!(w, "    ; bitpanda_id: {}", entry.id)?;
+    for (account, posting) in postings(journal, &entry.kind) {
+        writeln!(w, "    {account}  {posting}")?;
+    }
+
+    Ok(())
+}
+
+fn postings(journal: &Journal, kind: &EntryKind) -> Vec<(String, String)> {
+    let accounts = &journal.accounts;
+    match kind {
+        EntryKind::Trade(legs) => legs
+            .iter()
+            .flat_map(|leg| {
+                let TradeLeg {
+                    r#type,
+                    asset,
+                    amount: asset_amount,
+                    fiat,
+                    fiat_amount,
+                    ..
+                } = leg;
+                let (asset_amount, fiat_amount) = match r#type {
+                    TradeType::Buy => (*asset_amount, -*fiat_amount),
+                    TradeType::Sell => (-*asset_amount, *fiat_amount),
+                };
+                [
+                    (
+                        accounts.asset(asset),
+                        format!(
+                            "{} @@ {}",
+                            amount(asset_amount, asset),
+                            amount(fiat_amount.abs(), fiat)
+                        ),
+                    ),
+                    (accounts.asset(fiat), amount(fiat_amount, fiat)),
+                ]
+            })
+            .collect(),
+        EntryKind::Transfer(Transfer {
+            direction,
+            commodity,
+            amount: transferred,
+            fee,
+            ..
+        }) => {
+            let (wallet, external) = match direction {
+                Direction::Incoming => (*transferred - *fee, -*transferred),
+                Direction::Outgoing => (-(*transferred + *fee), *transferred),
+            };
+            let mut postings = vec![(accounts.asset(commodity), amount(wallet, commodity))];
+            if !fee.is_zero() {
+                postings.push((accounts.fees.clone(), amount(*fee, commodity)));
+            }
+            postings.push((accounts.external.clone(), amount(external, commodity)));
+            postings
+        }
+    }
+}
+
+fn amount(value: Decimal, symbol: &str) -> String {
+    format!("{} {}", decimal(value), commodity(symbol))
+}
+
+/// Commodities which are not made of letters only must be quoted
+fn commodity(symbol: &str) -> String {
+    if symbol.chars().all(|c| c.is_ascii_alphabetic()) {
+        symbol.to_string()
+    } else {
+        format!("\"{symbol}\"")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::export::journal::test::journal;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_write_ledger() {
+        let mut output = Vec::new();
+        write(&journal(), &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"2023-01-02 * Deposit EUR
+    ; bitpanda_id: fiat-tx-1
+    Assets:Bitpanda:EUR  1000 EUR
+    Equity:Bitpanda:External  -1000 EUR
+
+P 2023-01-10 BTC 20000 EUR
+2023-01-10 * Buy BTC
+    ; bitpanda_id: trade-1
+    Assets:Bitpanda:BTC  0.01 BTC @@ 200 EUR
+    Assets:Bitpanda:EUR  -200 EUR
+
+P 2023-02-01 BTC 22000 EUR
+P 2023-02-01 ETH 1100 EUR
+2023-02-01 * Swap BTC to ETH
+    ; bitpanda_id: trade-2
+    Assets:Bitpanda:BTC  -0.005 BTC @@ 110 EUR
+    Assets:Bitpanda:EUR  110 EUR
+    Assets:Bitpanda:ETH  0.1 ETH @@ 110 EUR
+    Assets:Bitpanda:EUR  -110 EUR
+
+2023-03-01 * Withdrawal ETH
+    ; bitpanda_id: crypto-tx-1
+    Assets:Bitpanda:ETH  -0.051 ETH
+    Expenses:Bitpanda:Fees  0.001 ETH
+    Equity:Bitpanda:External  0.05 ETH
+"#
+        );
+    }
+
+    #[test]
+    fn should_quote_commodities() {
+        assert_eq!(commodity("BTC"), "BTC");
+        assert_eq!(commodity("1INCH"), "\"1INCH\"");
+    }
+}
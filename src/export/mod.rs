@@ -0,0 +1,36 @@
+//! # Export
+//!
+//! Exporters of the Bitpanda models to file formats
+
+pub mod beancount;
+pub mod csv;
+pub mod journal;
+pub mod ledger;
+pub mod tax_tool;
+
+use std::io;
+
+use thiserror::Error;
+
+/// Export result
+pub type ExportResult<T> = Result<T, ExportError>;
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("CSV error: {0}")]
+    Csv(::csv::Error),
+    #[error("IO error: {0}")]
+    Io(io::Error),
+}
+
+impl From<::csv::Error> for ExportError {
+    fn from(value: ::csv::Error) -> Self {
+        Self::Csv(value)
+    }
+}
+
+impl From<io::Error> for ExportError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
@@ -0,0 +1,375 @@
+//! # Tax tool
+//!
+//! Export a [`Journal`] in the generic CSV import formats of the crypto tax tools.
+//!
+//! Each trade is a single row, swaps included, where the sent and the received commodities are the sold and
+//! the bought ones. Each transfer is a row too, with its fee in the fee columns:
+//!
+//! | Transaction         | Koinly label | CoinTracking type | CoinTracker tag |
+//! |---------------------|--------------|-------------------|-----------------|
+//! | incoming transfer\* | `income`     | `Income`          | `payment`       |
+//! | refund              |              | `Deposit`         |                 |
+//! | other incoming      |              | `Deposit`         |                 |
+//! | outgoing            |              | `Withdrawal`      |                 |
+//!
+//! \* only with [`TaxToolOptions::transfers_as_income`], otherwise incoming transfers are deposits.
+//!
+//! All dates are written in UTC. The Bitpanda id goes to the `Tx-ID` column of CoinTracking and to the description
+//! of Koinly, since its `TxHash` column is for on-chain hashes; CoinTracker has no column for it.
+
+use std::io;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use super::journal::{decimal, Direction, Entry, EntryKind, Journal, TradeLeg};
+use super::ExportResult;
+use crate::model::{TradeType, TransactionType};
+
+/// Crypto tax tool to export to
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum TaxTool {
+    /// Koinly universal CSV
+    Koinly,
+    /// CoinTracking CSV import
+    CoinTracking,
+    /// CoinTracker CSV import
+    CoinTracker,
+}
+
+impl TaxTool {
+    fn header(&self) -> &'static [&'static str] {
+        match self {
+            Self::Koinly => &[
+                "Date",
+                "Sent Amount",
+                "Sent Currency",
+                "Received Amount",
+                "Received Currency",
+                "Fee Amount",
+                "Fee Currency",
+                "Net Worth Amount",
+                "Net Worth Currency",
+                "Label",
+                "Description",
+                "TxHash",
+            ],
+            Self::CoinTracking => &[
+                "Type",
+                "Buy Amount",
+                "Buy Currency",
+                "Sell Amount",
+                "Sell Currency",
+                "Fee",
+                "Fee Currency",
+                "Exchange",
+                "Trade-Group",
+                "Comment",
+                "Date",
+                "Tx-ID",
+            ],
+            Self::CoinTracker => &[
+                "Date",
+                "Received Quantity",
+                "Received Currency",
+                "Sent Quantity",
+                "Sent Currency",
+                "Fee Amount",
+                "Fee Currency",
+                "Tag",
+            ],
+        }
+    }
+
+    fn fields(&self, row: &Row) -> Vec<String> {
+        let (received_amount, received_currency) = amount(&row.received);
+        let (sent_amount, sent_currency) = amount(&row.sent);
+        let (fee_amount, fee_currency) = amount(&row.fee);
+        match self {
+            Self::Koinly => {
+                let (worth_amount, worth_currency) = amount(&row.worth);
+                vec![
+                    row.datetime.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+                    sent_amount,
+                    sent_currency,
+                    received_amount,
+                    received_currency,
+                    fee_amount,
+                    fee_currency,
+                    worth_amount,
+                    worth_currency,
+                    match row.kind {
+                        RowKind::Income => "income",
+                        RowKind::Trade | RowKind::Deposit | RowKind::Withdrawal => "",
+                    }
+                    .to_string(),
+                    format!("{} (Bitpanda id {})", row.description, row.id),
+                    // Bitpanda ids aren't on-chain hashes, which Koinly matches transactions with
+                    String::new(),
+                ]
+            }
+            Self::CoinTracking => vec![
+                match row.kind {
+                    RowKind::Trade => "Trade",
+                    RowKind::Deposit => "Deposit",
+                    RowKind::Income => "Income",
+                    RowKind::Withdrawal => "Withdrawal",
+                }
+                .to_string(),
+                received_amount,
+                received_currency,
+                sent_amount,
+                sent_currency,
+                fee_amount,
+                fee_currency,
+                "Bitpanda".to_string(),
+                String::new(),
+                row.description.clone(),
+                row.datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+                row.id.clone(),
+            ],
+            Self::CoinTracker => vec![
+                row.datetime.format("%m/%d/%Y %H:%M:%S").to_string(),
+                received_amount,
+                received_currency,
+                sent_amount,
+                sent_currency,
+                fee_amount,
+                fee_currency,
+                match row.kind {
+                    RowKind::Income => "payment",
+                    RowKind::Trade | RowKind::Deposit | RowKind::Withdrawal => "",
+                }
+                .to_string(),
+            ],
+        }
+    }
+}
+
+/// Tax tool export options
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct TaxToolOptions {
+    transfers_as_income: bool,
+}
+
+impl TaxToolOptions {
+    /// Set whether incoming transfers are exported as income, e.g. when they are all rewards (default: false)
+    pub fn transfers_as_income(mut self, transfers_as_income: bool) -> Self {
+        self.transfers_as_income = transfers_as_income;
+
+        self
+    }
+}
+
+/// Write the journal to `w` in the import format of `tool`
+pub fn write<W: io::Write>(
+    journal: &Journal,
+    tool: TaxTool,
+    options: TaxToolOptions,
+    w: W,
+) -> ExportResult<()> {
+    let mut writer = csv::Writer::from_writer(w);
+    writer.write_record(tool.header())?;
+    for entry in &journal.entries {
+        writer.write_record(tool.fields(&Row::new(entry, &options)))?;
+    }
+
+    writer.flush().map_err(Into::into)
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum RowKind {
+    Trade,
+    Deposit,
+    Income,
+    Withdrawal,
+}
+
+/// A row of the export, shared by all the tools
+struct Row {
+    kind: RowKind,
+    datetime: DateTime<Utc>,
+    received: Option<(Decimal, String)>,
+    sent: Option<(Decimal, String)>,
+    fee: Option<(Decimal, String)>,
+    /// Value of the row in a fiat currency
+    worth: Option<(Decimal, String)>,
+    description: String,
+    id: String,
+}
+
+impl Row {
+    fn new(entry: &Entry, options: &TaxToolOptions) -> Self {
+        let mut row = Self {
+            kind: RowKind::Trade,
+            datetime: entry.datetime.with_timezone(&Utc),
+            received: None,
+            sent: None,
+            fee: None,
+            worth: None,
+            description: entry.narration.clone(),
+            id: entry.id.clone(),
+        };
+        match &entry.kind {
+            EntryKind::Trade(legs) => {
+                for TradeLeg {
+                    r#type,
+                    asset,
+                    amount,
+                    fiat,
+                    fiat_amount,
+                    ..
+                } in legs
+                {
+                    let asset = Some((*amount, asset.clone()));
+                    let fiat = Some((*fiat_amount, fiat.clone()));
+                    // on swaps the fiat legs cancel out
+                    match r#type {
+                        TradeType::Buy => {
+                            row.received = asset;
+                            row.sent = row.sent.take().or_else(|| fiat.clone());
+                        }
+                        TradeType::Sell => {
+                            row.sent = asset;
+                            row.received = row.received.take().or_else(|| fiat.clone());
+                        }
+                    }
+                    row.worth = fiat;
+                }
+            }
+            EntryKind::Transfer(transfer) => {
+                let amount = Some((transfer.amount, transfer.commodity.clone()));
+                match transfer.direction {
+                    Direction::Incoming => {
+                        row.kind = match transfer.transaction_type {
+                            TransactionType::Transfer if options.transfers_as_income => {
+                                RowKind::Income
+                            }
+                            _ => RowKind::Deposit,
+                        };
+                        row.received = amount;
+                    }
+                    Direction::Outgoing => {
+                        row.kind = RowKind::Withdrawal;
+                        row.sent = amount;
+                    }
+                }
+                if !transfer.fee.is_zero() {
+                    row.fee = Some((transfer.fee, transfer.commodity.clone()));
+                }
+                row.worth = transfer
+                    .unit_value_eur
+                    .map(|unit_value| (unit_value * transfer.amount, "EUR".to_string()));
+            }
+        }
+
+        row
+    }
+}
+
+fn amount(value: &Option<(Decimal, String)>) -> (String, String) {
+    value
+        .as_ref()
+        .map(|(amount, currency)| (decimal(*amount), currency.clone()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::export::journal::test::journal;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_export_koinly() {
+        assert_eq!(
+            export(TaxTool::Koinly),
+            "Date,Sent Amount,Sent Currency,Received Amount,Received Currency,Fee Amount,Fee Currency,Net Worth Amount,Net Worth Currency,Label,Description,TxHash
+2023-01-02 11:00:00 UTC,,,1000,EUR,,,,,,Deposit EUR (Bitpanda id fiat-tx-1),
+2023-01-10 08:30:00 UTC,200,EUR,0.01,BTC,,,200,EUR,,Buy BTC (Bitpanda id trade-1),
+2023-02-01 09:00:00 UTC,0.005,BTC,0.1,ETH,,,110,EUR,,Swap BTC to ETH (Bitpanda id trade-2),
+2023-03-01 07:00:00 UTC,0.05,ETH,,,0.001,ETH,55,EUR,,Withdrawal ETH (Bitpanda id crypto-tx-1),
+"
+        );
+    }
+
+    #[test]
+    fn should_export_cointracking() {
+        assert_eq!(
+            export(TaxTool::CoinTracking),
+            "Type,Buy Amount,Buy Currency,Sell Amount,Sell Currency,Fee,Fee Currency,Exchange,Trade-Group,Comment,Date,Tx-ID
+Deposit,1000,EUR,,,,,Bitpanda,,Deposit EUR,2023-01-02 11:00:00,fiat-tx-1
+Trade,0.01,BTC,200,EUR,,,Bitpanda,,Buy BTC,2023-01-10 08:30:00,trade-1
+Trade,0.1,ETH,0.005,BTC,,,Bitpanda,,Swap BTC to ETH,2023-02-01 09:00:00,trade-2
+Withdrawal,,,0.05,ETH,0.001,ETH,Bitpanda,,Withdrawal ETH,2023-03-01 07:00:00,crypto-tx-1
+"
+        );
+    }
+
+    #[test]
+    fn should_export_cointracker() {
+        assert_eq!(
+            export(TaxTool::CoinTracker),
+            "Date,Received Quantity,Received Currency,Sent Quantity,Sent Currency,Fee Amount,Fee Currency,Tag
+01/02/2023 11:00:00,1000,EUR,,,,,
+01/10/2023 08:30:00,0.01,BTC,200,EUR,,,
+02/01/2023 09:00:00,0.1,ETH,0.005,BTC,,,
+03/01/2023 07:00:00,,,0.05,ETH,0.001,ETH,
+"
+        );
+    }
+
+    #[test]
+    fn should_tag_incoming_transfers_as_income() {
+        let entry = transfer(TransactionType::Transfer, Direction::Incoming);
+        let options = TaxToolOptions::default().transfers_as_income(true);
+        let row = Row::new(&entry, &options);
+        assert_eq!(row.kind, RowKind::Income);
+        assert_eq!(TaxTool::Koinly.fields(&row)[9], "income");
+        assert_eq!(TaxTool::CoinTracking.fields(&row)[0], "Income");
+        assert_eq!(TaxTool::CoinTracker.fields(&row)[7], "payment");
+
+        // opt-in
+        let row = Row::new(&entry, &TaxToolOptions::default());
+        assert_eq!(row.kind, RowKind::Deposit);
+    }
+
+    #[test]
+    fn should_not_tag_outgoing_transfers_as_income() {
+        let entry = transfer(TransactionType::Transfer, Direction::Outgoing);
+        let options = TaxToolOptions::default().transfers_as_income(true);
+        let row = Row::new(&entry, &options);
+        assert_eq!(row.kind, RowKind::Withdrawal);
+        assert_eq!(TaxTool::CoinTracking.fields(&row)[0], "Withdrawal");
+    }
+
+    #[test]
+    fn should_export_refunds_as_deposits() {
+        let entry = transfer(TransactionType::Refund, Direction::Incoming);
+        let options = TaxToolOptions::default().transfers_as_income(true);
+        let row = Row::new(&entry, &options);
+        assert_eq!(row.kind, RowKind::Deposit);
+        assert_eq!(TaxTool::Koinly.fields(&row)[9], "");
+        assert_eq!(TaxTool::CoinTracking.fields(&row)[0], "Deposit");
+        assert_eq!(TaxTool::CoinTracker.fields(&row)[7], "");
+    }
+
+    /// The deposit of the journal, with `transaction_type` and direction
+    fn transfer(transaction_type: TransactionType, direction: Direction) -> Entry {
+        let mut entry = journal().entries.remove(0);
+        let EntryKind::Transfer(transfer) = &mut entry.kind else {
+            panic!("not a transfer");
+        };
+        transfer.transaction_type = transaction_type;
+        transfer.direction = direction;
+
+        entry
+    }
+
+    fn export(tool: TaxTool) -> String {
+        let mut output = Vec::new();
+        write(&journal(), tool, TaxToolOptions::default(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+}
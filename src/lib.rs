@@ -12,11 +12,14 @@
 //!
 //! Supported features are:
 //!
+//! - `blocking`: synchronous client, for programs without an async runtime
 //! - `no-log`: disable logging
+//! - `store`: persist and sync the history into a local SQLite database
+//! - `testing`: offline mock server of the Bitpanda API, to test without network
 //!
 //! ## Example
 //!
-//! ```rust
+//! ```rust,no_run
 //! use bitpanda_api::Client;
 //! use bitpanda_api::model::AssetClass;
 //! use bitpanda_api::model::ohlc::Period;
@@ -24,7 +27,7 @@
 //! #[tokio::main]
 //! async fn main() {
 //!
-//!     let client = Client::default().x_apikey(env!("X_API_KEY"));
+//!     let client = Client::default().x_apikey(std::env::var("X_API_KEY").unwrap());
 //!
 //!     // collect my last 20 trades
 //!     client.get_trades_ex(Some(20)).await.expect("failed to collect trades");
@@ -42,6 +45,94 @@
 //! }
 //! ```
 //!
+//! ### Streaming
+//!
+//! Trades and transactions can also be streamed page by page, which keeps memory bounded
+//! and allows to stop the pagination at any time.
+//!
+//! ```rust,no_run
+//! use bitpanda_api::Client;
+//! use futures_util::TryStreamExt;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!
+//!     let client = Client::default().x_apikey(std::env::var("X_API_KEY").unwrap());
+//!
+//!     let trades = client.trades_stream();
+//!     futures_util::pin_mut!(trades);
+//!     while let Some(trade) = trades.try_next().await.unwrap() {
+//!         println!("{} {} {}", trade.datetime, trade.symbol, trade.amount_asset);
+//!     }
+//! }
+//! ```
+//!
+//! ### Recording
+//!
+//! The interactions with the API can be recorded to a cassette, and later replayed without network,
+//! to write deterministic tests against genuine payloads. The api key is scrubbed from the recording.
+//!
+//! ```rust,no_run
+//! use bitpanda_api::Client;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!
+//!     let client = Client::builder()
+//!         .x_apikey(std::env::var("X_API_KEY").unwrap())
+//!         .record("trades.json")
+//!         .build();
+//!     let recorded = client.get_trades().await.unwrap();
+//!
+//!     // any api key can be used to replay
+//!     let client = Client::builder().x_apikey("replay").replay("trades.json").build();
+//!     assert_eq!(client.get_trades().await.unwrap(), recorded);
+//! }
+//! ```
+//!
+//! ### Raw attributes
+//!
+//! Attributes which are not mapped by the models yet can be read from the raw JSON:API `attributes`
+//! object each resource was parsed from, returned by the [`Client::with_raw`] view.
+//!
+//! ```rust,no_run
+//! use bitpanda_api::{Client, TradeFilter};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!
+//!     let client = Client::default().x_apikey(std::env::var("X_API_KEY").unwrap());
+//!
+//!     for (trade, attributes) in client.with_raw().get_trades(TradeFilter::default()).await.unwrap() {
+//!         println!("{} best price: {}", trade.id, attributes["best_price"]);
+//!     }
+//! }
+//! ```
+//!
+//! ### Transport
+//!
+//! Requests are sent with [`reqwest`] by default, but any [`Transport`] can be plugged in,
+//! e.g. another HTTP client, a middleware stack or an in-process fake.
+//!
+//! ```rust,no_run
+//! use bitpanda_api::{ApiError, Client, HttpRequest, HttpResponse, ReqwestTransport, Transport};
+//! use futures_util::future::BoxFuture;
+//!
+//! /// Logs each request before sending it with reqwest
+//! struct Logging(ReqwestTransport);
+//!
+//! impl Transport for Logging {
+//!     fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, ApiError>> {
+//!         println!("GET {}", request.url);
+//!         self.0.send(request)
+//!     }
+//! }
+//!
+//! let client = Client::builder()
+//!     .transport(Logging(ReqwestTransport::default()))
+//!     .build();
+//! ```
+//!
 
 #![doc(html_playground_url = "https://play.rust-lang.org")]
 
@@ -50,7 +141,20 @@ extern crate log;
 #[macro_use]
 extern crate serde;
 
+pub mod accounting;
 mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod export;
 pub mod model;
+pub mod portfolio;
+#[cfg(feature = "store")]
+pub mod store;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
-pub use api::{ApiError, Client};
+pub use api::{
+    ApiError, Cassette, Client, ClientBuilder, HttpRequest, HttpResponse, Interaction, RateLimit,
+    RawClient, RecordingTransport, ReplayTransport, ReqwestTransport, RetryPolicy, TradeFilter,
+    TransactionFilter, Transport,
+};
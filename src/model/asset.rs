@@ -1,7 +1,10 @@
 //! # Asset
 
+use std::fmt;
+
 /// Describes the asset class
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case", into = "&'static str")]
 pub enum AssetClass {
     Commodity,
     Cryptocurrency,
@@ -11,22 +14,42 @@ pub enum AssetClass {
     Stock,
 }
 
-impl ToString for AssetClass {
-    fn to_string(&self) -> String {
+impl AssetClass {
+    /// Name of the class, as serialized (e.g. `cryptoindex`)
+    pub fn as_str(&self) -> &'static str {
         match self {
+            Self::Commodity => "commodity",
+            Self::Cryptocurrency => "cryptocurrency",
+            Self::Cryptoindex => "cryptoindex",
+            Self::Etf => "etf",
+            Self::Metal => "metal",
+            Self::Stock => "stock",
+        }
+    }
+}
+
+impl From<AssetClass> for &'static str {
+    fn from(value: AssetClass) -> Self {
+        value.as_str()
+    }
+}
+
+impl fmt::Display for AssetClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
             Self::Commodity => "etc",
             Self::Cryptocurrency => "cryptocoin",
             Self::Cryptoindex => "cryptocoin",
             Self::Etf => "etf",
             Self::Metal => "metal",
             Self::Stock => "stock",
-        }
-        .to_string()
+        };
+        write!(f, "{s}")
     }
 }
 
 /// A bitpanda asset
-#[derive(Debug, Clone, Hash, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Hash, Deserialize, Serialize, PartialEq, Eq)]
 pub struct Asset {
     pub id: String,
     /// Identifier used to get the OHLC
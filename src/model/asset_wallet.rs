@@ -5,7 +5,7 @@ use super::AssetClass;
 use rust_decimal::Decimal;
 
 /// A Bitpanda wallet for a certain asset
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct AssetWallet {
     pub asset_id: String,
     pub asset_symbol: String,
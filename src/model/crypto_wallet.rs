@@ -7,7 +7,7 @@ mod transaction;
 pub use transaction::CryptoWalletTransaction;
 
 /// Defines a Bitpanda wallet for cryptocurrencies
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub struct CryptoWallet {
     pub balance: Decimal,
     pub cryptocoin_id: String,
@@ -4,7 +4,7 @@ use rust_decimal::Decimal;
 use crate::model::{InOrOut, TransactionStatus, TransactionType};
 
 /// Defines a Bitpanda transaction for a cryptocurrencies wallet
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub struct CryptoWalletTransaction {
     pub amount_eur: Decimal,
     pub amount: Decimal,
@@ -7,7 +7,7 @@ mod transaction;
 pub use transaction::FiatWalletTransaction;
 
 /// Defines a Bitpanda wallet for fiat currencies
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub struct FiatWallet {
     pub balance: Decimal,
     pub fiat_id: String,
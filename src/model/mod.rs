@@ -1,10 +1,20 @@
 //! # Bitpanda API model
+//!
+//! All the models can be serialized and deserialized with serde. The JSON representation is stable:
+//!
+//! - structs are objects with the field names as keys (`r#type` is `type`);
+//! - enums are lowercase strings in snake case, matching Bitpanda's ones (e.g. `"unconfirmed_transaction_out"`),
+//!   except for [`AssetClass`], whose variants are all distinct (e.g. `"cryptocurrency"`, `"cryptoindex"`),
+//!   and [`ohlc::Period::FiveYears`], which is `"five-years"`;
+//! - decimals are strings, to preserve their precision;
+//! - dates are RFC 3339 strings.
 
 mod asset;
 mod asset_wallet;
 pub mod crypto_wallet;
 pub mod fiat_wallet;
 pub mod ohlc;
+mod ticker;
 mod trade;
 mod transaction;
 
@@ -13,5 +23,200 @@ pub use asset_wallet::AssetWallet;
 pub use crypto_wallet::CryptoWallet;
 pub use fiat_wallet::FiatWallet;
 pub use ohlc::OpenHighLowCloseChart;
+pub use ticker::Ticker;
 pub use trade::{Trade, TradeStatus, TradeType};
 pub use transaction::{InOrOut, TransactionStatus, TransactionType};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::collections::HashMap;
+    use std::fmt::Debug;
+
+    use pretty_assertions::assert_eq;
+    use rust_decimal_macros::dec;
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+
+    use crypto_wallet::CryptoWalletTransaction;
+    use fiat_wallet::FiatWalletTransaction;
+    use ohlc::{Ohlc, Period};
+
+    use crate::testing::fixture::{datetime, TradeBuilder};
+
+    #[test]
+    fn should_serialize_enums_as_bitpanda_strings() {
+        assert_eq!(to_json(&TradeType::Buy), r#""buy""#);
+        assert_eq!(to_json(&TradeStatus::Canceled), r#""canceled""#);
+        assert_eq!(
+            to_json(&TransactionStatus::UnconfirmedTransactionOut),
+            r#""unconfirmed_transaction_out""#
+        );
+        assert_eq!(to_json(&TransactionType::Withdrawal), r#""withdrawal""#);
+        assert_eq!(to_json(&InOrOut::Incoming), r#""incoming""#);
+        assert_eq!(to_json(&AssetClass::Cryptoindex), r#""cryptoindex""#);
+        assert_eq!(to_json(&Period::FiveYears), r#""five-years""#);
+    }
+
+    #[test]
+    fn should_preserve_unknown_enum_values() {
+        assert_eq!(
+            "settled".parse::<TradeStatus>().unwrap(),
+            TradeStatus::Unknown("settled".to_string())
+        );
+        assert_eq!(
+            "staking".parse::<TransactionType>().unwrap(),
+            TransactionType::Unknown("staking".to_string())
+        );
+        assert_eq!(
+            "finished".parse::<TransactionStatus>().unwrap(),
+            TransactionStatus::Finished
+        );
+        assert_eq!(
+            to_json(&TransactionStatus::Unknown("on_hold".to_string())),
+            r#""on_hold""#
+        );
+        assert_eq!(
+            InOrOut::Unknown("internal".to_string()).to_string(),
+            "internal"
+        );
+        round_trip(TradeStatus::Unknown("settled".to_string()));
+        round_trip(TransactionType::Unknown("staking".to_string()));
+        round_trip(TransactionStatus::Unknown("on_hold".to_string()));
+        round_trip(InOrOut::Unknown("internal".to_string()));
+        round_trip(InOrOut::Outgoing);
+    }
+
+    #[test]
+    fn should_round_trip_assets() {
+        round_trip(Asset {
+            id: "1".to_string(),
+            pid: "0b0e1b5c-0e7b-11ec-bd3c-0a7a5f4b5d7e".to_string(),
+            name: "Bitcoin".to_string(),
+            symbol: "BTC".to_string(),
+            r#type: AssetClass::Cryptocurrency,
+        });
+        round_trip(AssetWallet {
+            asset_id: "28".to_string(),
+            asset_symbol: "XAU".to_string(),
+            balance: dec!(1.5),
+            class: AssetClass::Metal,
+            deleted: false,
+            id: "wallet".to_string(),
+            is_default: true,
+            name: "Gold wallet".to_string(),
+        });
+        for class in [
+            AssetClass::Commodity,
+            AssetClass::Cryptocurrency,
+            AssetClass::Cryptoindex,
+            AssetClass::Etf,
+            AssetClass::Metal,
+            AssetClass::Stock,
+        ] {
+            round_trip(class);
+        }
+    }
+
+    #[test]
+    fn should_round_trip_wallets() {
+        round_trip(CryptoWallet {
+            balance: dec!(0.00124),
+            cryptocoin_id: "1".to_string(),
+            deleted: false,
+            id: "btc-wallet".to_string(),
+            is_default: true,
+            name: "BTC Wallet".to_string(),
+            pending_transactions_count: 0,
+            symbol: "BTC".to_string(),
+        });
+        round_trip(FiatWallet {
+            balance: dec!(100.50),
+            fiat_id: "1".to_string(),
+            id: "eur-wallet".to_string(),
+            name: "EUR Wallet".to_string(),
+            pending_transactions_count: 1,
+            symbol: "EUR".to_string(),
+        });
+    }
+
+    #[test]
+    fn should_round_trip_trades() {
+        let related = TradeBuilder::new("trade-2")
+            .datetime(datetime("2023-01-10T09:30:00+01:00"))
+            .build();
+        round_trip(
+            TradeBuilder::new("trade-1")
+                .r#type(TradeType::Sell)
+                .datetime(datetime("2023-01-10T09:30:00+01:00"))
+                .swap(related)
+                .build(),
+        );
+    }
+
+    #[test]
+    fn should_round_trip_transactions() {
+        round_trip(CryptoWalletTransaction {
+            amount_eur: dec!(55.00),
+            amount: dec!(0.05),
+            confirmations: 10,
+            cryptocoin_id: "5".to_string(),
+            current_fiat_amount: dec!(60),
+            current_fiat_id: "1".to_string(),
+            datetime: datetime("2023-03-01T08:00:00+01:00"),
+            fee: dec!(0.001),
+            id: "crypto-tx".to_string(),
+            in_or_out: InOrOut::Outgoing,
+            recipient: "0xabc".to_string(),
+            status: TransactionStatus::OpenInvitation,
+            transaction_type: TransactionType::Withdrawal,
+            wallet_id: "eth-wallet".to_string(),
+        });
+        round_trip(FiatWalletTransaction {
+            amount: dec!(1000),
+            datetime: datetime("2023-01-02T12:00:00+01:00"),
+            fee: dec!(0),
+            fiat_id: "1".to_string(),
+            id: "fiat-tx".to_string(),
+            in_or_out: InOrOut::Incoming,
+            status: TransactionStatus::Finished,
+            to_eur_rate: dec!(1),
+            transaction_type: TransactionType::Deposit,
+            user_id: "user".to_string(),
+            wallet_id: "eur-wallet".to_string(),
+        });
+    }
+
+    #[test]
+    fn should_round_trip_charts_and_tickers() {
+        round_trip(OpenHighLowCloseChart {
+            chart: vec![Ohlc {
+                close: dec!(21000.5),
+                high: dec!(21500),
+                low: dec!(20500),
+                open: dec!(20800.25),
+                time: datetime("2023-01-10T00:00:00+00:00"),
+            }],
+            period: Period::FiveYears,
+        });
+        round_trip(Ticker {
+            prices: HashMap::from([(
+                "BTC".to_string(),
+                HashMap::from([("EUR".to_string(), dec!(20000.12))]),
+            )]),
+        });
+    }
+
+    fn round_trip<T>(value: T)
+    where
+        T: Serialize + DeserializeOwned + Debug + PartialEq,
+    {
+        let json = to_json(&value);
+        assert_eq!(serde_json::from_str::<T>(&json).unwrap(), value);
+    }
+
+    fn to_json<T: Serialize>(value: &T) -> String {
+        serde_json::to_string(value).unwrap()
+    }
+}
@@ -2,18 +2,30 @@
 //!
 //! Api types for Open-high-low-close chart for assets
 
+use std::fmt;
+
 use chrono::{DateTime, FixedOffset};
 use rust_decimal::Decimal;
 
 /// Open high low close chart type
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub struct OpenHighLowCloseChart {
     pub chart: Vec<Ohlc>,
     pub period: Period,
 }
 
+impl OpenHighLowCloseChart {
+    /// Get the close of the most recent entry of the chart
+    pub fn last_close(&self) -> Option<Decimal> {
+        self.chart
+            .iter()
+            .max_by_key(|entry| entry.time)
+            .map(|entry| entry.close)
+    }
+}
+
 /// Defines an entry in the OHLC chart
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub struct Ohlc {
     pub close: Decimal,
     pub high: Decimal,
@@ -23,24 +35,26 @@ pub struct Ohlc {
 }
 
 /// A period which identifies the OHLC chart
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Deserialize)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Period {
     Day,
     Week,
     Month,
     Year,
+    #[serde(rename = "five-years")]
     FiveYears,
 }
 
-impl ToString for Period {
-    fn to_string(&self) -> String {
-        match self {
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
             Self::Day => "day",
             Self::Month => "month",
             Self::Week => "week",
             Self::Year => "year",
             Self::FiveYears => "five-years",
-        }
-        .to_string()
+        };
+        write!(f, "{s}")
     }
 }
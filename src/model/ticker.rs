@@ -0,0 +1,58 @@
+//! # Ticker
+//!
+//! Current prices of the assets on Bitpanda
+
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use super::{Asset, AssetWallet};
+
+/// Current prices of the assets on Bitpanda
+#[derive(Debug, Default, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Ticker {
+    /// Prices by asset symbol and then by fiat currency symbol
+    pub prices: HashMap<String, HashMap<String, Decimal>>,
+}
+
+impl Ticker {
+    /// Get the price of the asset with `symbol` in `currency`
+    pub fn price(&self, symbol: &str, currency: &str) -> Option<Decimal> {
+        self.prices
+            .get(symbol)
+            .and_then(|prices| prices.get(currency))
+            .copied()
+    }
+
+    /// Get the price of `asset` in `currency`
+    pub fn asset_price(&self, asset: &Asset, currency: &str) -> Option<Decimal> {
+        self.price(&asset.symbol, currency)
+    }
+
+    /// Get the price of the asset held in `wallet` in `currency`
+    pub fn asset_wallet_price(&self, wallet: &AssetWallet, currency: &str) -> Option<Decimal> {
+        self.price(&wallet.asset_symbol, currency)
+    }
+
+    /// Get the prices in all the currencies for the asset with `symbol`
+    pub fn prices_of(&self, symbol: &str) -> Option<&HashMap<String, Decimal>> {
+        self.prices.get(symbol)
+    }
+
+    /// Get the price in `currency` of all the assets by symbol
+    pub fn prices_in(&self, currency: &str) -> HashMap<String, Decimal> {
+        self.prices
+            .iter()
+            .filter_map(|(symbol, prices)| {
+                prices
+                    .get(currency)
+                    .map(|price| (symbol.to_string(), *price))
+            })
+            .collect()
+    }
+
+    /// Iterate over the symbols of the assets in the ticker
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.prices.keys().map(String::as_str)
+    }
+}
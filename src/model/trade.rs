@@ -2,6 +2,7 @@
 //!
 //! Datatypes for Bitpanda API trades
 
+use std::fmt;
 use std::str::FromStr;
 
 use chrono::{DateTime, FixedOffset};
@@ -10,7 +11,7 @@ use rust_decimal::Decimal;
 use crate::ApiError;
 
 /// A trade on the Bitpanda exchange
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub struct Trade {
     pub amount_asset: Decimal,
     pub amount_fiat: Decimal,
@@ -30,35 +31,78 @@ pub struct Trade {
 }
 
 /// Defines the trade status
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
 pub enum TradeStatus {
     Pending,
     Processing,
     Finished,
     Canceled,
+    /// A status this version of the library doesn't know yet
+    Unknown(String),
+}
+
+impl fmt::Display for TradeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Pending => "pending",
+            Self::Processing => "processing",
+            Self::Finished => "finished",
+            Self::Canceled => "canceled",
+            Self::Unknown(s) => s,
+        };
+        write!(f, "{s}")
+    }
 }
 
 impl FromStr for TradeStatus {
     type Err = ApiError;
 
+    /// Never fails: unknown statuses are parsed as [`TradeStatus::Unknown`]
     fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(value.to_string()))
+    }
+}
+
+impl From<String> for TradeStatus {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "pending" => Self::Pending,
+            "processing" => Self::Processing,
+            "finished" => Self::Finished,
+            "canceled" => Self::Canceled,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+impl From<TradeStatus> for String {
+    fn from(value: TradeStatus) -> Self {
         match value {
-            "pending" => Ok(Self::Pending),
-            "processing" => Ok(Self::Processing),
-            "finished" => Ok(Self::Finished),
-            "canceled" => Ok(Self::Canceled),
-            _ => Err(ApiError::UnexpectedValue(value.to_string())),
+            TradeStatus::Unknown(s) => s,
+            status => status.to_string(),
         }
     }
 }
 
 /// Defines the trade type
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Deserialize)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TradeType {
     Buy,
     Sell,
 }
 
+impl fmt::Display for TradeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Buy => "buy",
+            Self::Sell => "sell",
+        };
+        write!(f, "{s}")
+    }
+}
+
 impl FromStr for TradeType {
     type Err = ApiError;
 
@@ -1,9 +1,11 @@
+use std::fmt;
 use std::str::FromStr;
 
 use crate::ApiError;
 
 /// Transaction status
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
 pub enum TransactionStatus {
     Canceled,
     Finished,
@@ -12,11 +14,13 @@ pub enum TransactionStatus {
     Processing,
     Unconfirmed,
     UnconfirmedTransactionOut,
+    /// A status this version of the library doesn't know yet
+    Unknown(String),
 }
 
-impl ToString for TransactionStatus {
-    fn to_string(&self) -> String {
-        match self {
+impl fmt::Display for TransactionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
             TransactionStatus::Canceled => "canceled",
             TransactionStatus::Finished => "finished",
             TransactionStatus::OpenInvitation => "open_invitation",
@@ -24,30 +28,48 @@ impl ToString for TransactionStatus {
             TransactionStatus::Processing => "processing",
             TransactionStatus::Unconfirmed => "unconfirmed",
             TransactionStatus::UnconfirmedTransactionOut => "unconfirmed_transaction_out",
-        }
-        .to_string()
+            TransactionStatus::Unknown(s) => s,
+        };
+        write!(f, "{s}")
     }
 }
 
 impl FromStr for TransactionStatus {
     type Err = ApiError;
 
+    /// Never fails: unknown statuses are parsed as [`TransactionStatus::Unknown`]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "canceled" => Ok(Self::Canceled),
-            "finished" => Ok(Self::Finished),
-            "open_invitation" => Ok(Self::OpenInvitation),
-            "pending" => Ok(Self::Pending),
-            "processing" => Ok(Self::Processing),
-            "unconfirmed" => Ok(Self::Unconfirmed),
-            "unconfirmed_transaction_out" => Ok(Self::UnconfirmedTransactionOut),
-            _ => Err(ApiError::UnexpectedValue(s.to_string())),
+        Ok(Self::from(s.to_string()))
+    }
+}
+
+impl From<String> for TransactionStatus {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "canceled" => Self::Canceled,
+            "finished" => Self::Finished,
+            "open_invitation" => Self::OpenInvitation,
+            "pending" => Self::Pending,
+            "processing" => Self::Processing,
+            "unconfirmed" => Self::Unconfirmed,
+            "unconfirmed_transaction_out" => Self::UnconfirmedTransactionOut,
+            _ => Self::Unknown(s),
+        }
+    }
+}
+
+impl From<TransactionStatus> for String {
+    fn from(value: TransactionStatus) -> Self {
+        match value {
+            TransactionStatus::Unknown(s) => s,
+            status => status.to_string(),
         }
     }
 }
 
 /// Transaction type
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
 pub enum TransactionType {
     Buy,
     Deposit,
@@ -56,55 +78,104 @@ pub enum TransactionType {
     Sell,
     Transfer,
     Withdrawal,
+    /// A type this version of the library doesn't know yet
+    Unknown(String),
 }
 
 impl FromStr for TransactionType {
     type Err = ApiError;
 
+    /// Never fails: unknown types are parsed as [`TransactionType::Unknown`]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "buy" => Ok(Self::Buy),
-            "deposit" => Ok(Self::Deposit),
-            "ico" => Ok(Self::Ico),
-            "refund" => Ok(Self::Refund),
-            "sell" => Ok(Self::Sell),
-            "transfer" => Ok(Self::Transfer),
-            "withdrawal" => Ok(Self::Withdrawal),
-            _ => Err(ApiError::UnexpectedValue(s.to_string())),
+        Ok(Self::from(s.to_string()))
+    }
+}
+
+impl From<String> for TransactionType {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "buy" => Self::Buy,
+            "deposit" => Self::Deposit,
+            "ico" => Self::Ico,
+            "refund" => Self::Refund,
+            "sell" => Self::Sell,
+            "transfer" => Self::Transfer,
+            "withdrawal" => Self::Withdrawal,
+            _ => Self::Unknown(s),
+        }
+    }
+}
+
+impl From<TransactionType> for String {
+    fn from(value: TransactionType) -> Self {
+        match value {
+            TransactionType::Unknown(s) => s,
+            transaction_type => transaction_type.to_string(),
         }
     }
 }
 
-impl ToString for TransactionType {
-    fn to_string(&self) -> String {
-        match self {
+impl fmt::Display for TransactionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
             TransactionType::Buy => "buy",
             TransactionType::Sell => "sell",
             TransactionType::Deposit => "deposit",
             TransactionType::Ico => "ico",
-            TransactionType::Refund => "ico",
+            TransactionType::Refund => "refund",
             TransactionType::Transfer => "transfer",
             TransactionType::Withdrawal => "withdrawal",
-        }
-        .to_string()
+            TransactionType::Unknown(s) => s,
+        };
+        write!(f, "{s}")
     }
 }
 
 /// Transaction "direction"
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
 pub enum InOrOut {
     Incoming,
     Outgoing,
+    /// A direction this version of the library doesn't know yet
+    Unknown(String),
+}
+
+impl fmt::Display for InOrOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Incoming => "incoming",
+            Self::Outgoing => "outgoing",
+            Self::Unknown(s) => s,
+        };
+        write!(f, "{s}")
+    }
 }
 
 impl FromStr for InOrOut {
     type Err = ApiError;
 
+    /// Never fails: unknown directions are parsed as [`InOrOut::Unknown`]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "incoming" => Ok(Self::Incoming),
-            "outgoing" => Ok(Self::Outgoing),
-            _ => Err(ApiError::UnexpectedValue(s.to_string())),
+        Ok(Self::from(s.to_string()))
+    }
+}
+
+impl From<String> for InOrOut {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "incoming" => Self::Incoming,
+            "outgoing" => Self::Outgoing,
+            _ => Self::Unknown(s),
+        }
+    }
+}
+
+impl From<InOrOut> for String {
+    fn from(value: InOrOut) -> Self {
+        match value {
+            InOrOut::Unknown(s) => s,
+            direction => direction.to_string(),
         }
     }
 }
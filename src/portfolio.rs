@@ -0,0 +1,242 @@
+//! # Portfolio
+//!
+//! Valuation of the user's wallets with the current prices
+
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::model::{AssetClass, AssetWallet, FiatWallet, Ticker};
+
+/// The user's holdings valued in a fiat currency
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Portfolio {
+    /// Fiat currency the portfolio is valued in
+    pub currency: String,
+    /// Holdings, one per symbol
+    pub positions: Vec<Position>,
+}
+
+/// A holding of the portfolio
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Position {
+    pub symbol: String,
+    pub kind: PositionKind,
+    /// Sum of the balances of all the wallets for the symbol
+    pub balance: Decimal,
+    /// Price of the symbol in the portfolio currency; `None` if the price is unknown
+    pub price: Option<Decimal>,
+    /// Value of the position in the portfolio currency; `None` if the price is unknown
+    pub value: Option<Decimal>,
+}
+
+/// Describes what kind of holding a position is
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum PositionKind {
+    Asset(AssetClass),
+    Fiat,
+}
+
+impl Portfolio {
+    /// Build a portfolio out of the user's wallets, valued in `currency` with the prices from `ticker`.
+    ///
+    /// Deleted and empty wallets are ignored, while wallets of the same symbol are merged into a single position.
+    /// Fiat wallets are valued only if they are in the portfolio currency, since the ticker doesn't provide exchange rates.
+    /// Positions without a price are kept and reported by [`Portfolio::missing_prices`]
+    pub fn new(
+        asset_wallets: &[AssetWallet],
+        fiat_wallets: &[FiatWallet],
+        ticker: &Ticker,
+        currency: &str,
+    ) -> Self {
+        let mut positions: Vec<Position> = Vec::new();
+
+        let assets = asset_wallets
+            .iter()
+            .filter(|wallet| !wallet.deleted)
+            .map(|wallet| {
+                (
+                    wallet.asset_symbol.as_str(),
+                    PositionKind::Asset(wallet.class),
+                    wallet.balance,
+                    ticker.asset_wallet_price(wallet, currency),
+                )
+            });
+        let fiats = fiat_wallets.iter().map(|wallet| {
+            (
+                wallet.symbol.as_str(),
+                PositionKind::Fiat,
+                wallet.balance,
+                (wallet.symbol == currency).then_some(Decimal::ONE),
+            )
+        });
+
+        for (symbol, kind, balance, price) in assets.chain(fiats) {
+            if balance.is_zero() {
+                continue;
+            }
+            match positions
+                .iter_mut()
+                .find(|position| position.symbol == symbol && position.kind == kind)
+            {
+                Some(position) => position.balance += balance,
+                None => positions.push(Position {
+                    symbol: symbol.to_string(),
+                    kind,
+                    balance,
+                    price,
+                    value: None,
+                }),
+            }
+        }
+
+        for position in positions.iter_mut() {
+            position.value = position.price.map(|price| price * position.balance);
+        }
+
+        Self {
+            currency: currency.to_string(),
+            positions,
+        }
+    }
+
+    /// Total value of the positions with a known price
+    pub fn total_value(&self) -> Decimal {
+        self.positions
+            .iter()
+            .filter_map(|position| position.value)
+            .sum()
+    }
+
+    /// Iterate over the positions whose price is unknown, and so are excluded from the total value
+    pub fn missing_prices(&self) -> impl Iterator<Item = &Position> {
+        self.positions
+            .iter()
+            .filter(|position| position.value.is_none())
+    }
+
+    /// Get the allocation percentage (0-100) of the total value for each kind of position
+    pub fn allocation(&self) -> HashMap<PositionKind, Decimal> {
+        let total = self.total_value();
+        let mut allocation = HashMap::new();
+        if total.is_zero() {
+            return allocation;
+        }
+
+        for position in self.positions.iter() {
+            if let Some(value) = position.value {
+                *allocation.entry(position.kind).or_insert(Decimal::ZERO) +=
+                    value / total * Decimal::ONE_HUNDRED;
+            }
+        }
+
+        allocation
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn should_value_portfolio() {
+        let portfolio = Portfolio::new(
+            &[
+                asset_wallet("BTC", AssetClass::Cryptocurrency, dec!(0.5), false),
+                asset_wallet("BTC", AssetClass::Cryptocurrency, dec!(0.25), false),
+                asset_wallet("ETH", AssetClass::Cryptocurrency, dec!(3), true),
+                asset_wallet("XAU", AssetClass::Metal, dec!(10), false),
+                asset_wallet("DOGE", AssetClass::Cryptocurrency, dec!(0), false),
+                asset_wallet("NEW", AssetClass::Stock, dec!(2), false),
+            ],
+            &[fiat_wallet("EUR", dec!(500)), fiat_wallet("USD", dec!(100))],
+            &ticker(),
+            "EUR",
+        );
+
+        assert_eq!(portfolio.currency, "EUR");
+        assert_eq!(portfolio.positions.len(), 5);
+        let btc = &portfolio.positions[0];
+        assert_eq!(btc.symbol, "BTC");
+        assert_eq!(btc.balance, dec!(0.75));
+        assert_eq!(btc.price, Some(dec!(20000)));
+        assert_eq!(btc.value, Some(dec!(15000)));
+        assert_eq!(portfolio.total_value(), dec!(16000));
+        assert_eq!(
+            portfolio
+                .missing_prices()
+                .map(|position| position.symbol.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["NEW", "USD"]
+        );
+
+        let allocation = portfolio.allocation();
+        assert_eq!(
+            allocation
+                .get(&PositionKind::Asset(AssetClass::Cryptocurrency))
+                .copied(),
+            Some(dec!(93.75))
+        );
+        assert_eq!(
+            allocation
+                .get(&PositionKind::Asset(AssetClass::Metal))
+                .copied(),
+            Some(dec!(3.125))
+        );
+        assert_eq!(
+            allocation.get(&PositionKind::Fiat).copied(),
+            Some(dec!(3.125))
+        );
+        assert!(!allocation.contains_key(&PositionKind::Asset(AssetClass::Stock)));
+    }
+
+    #[test]
+    fn should_get_empty_allocation_for_empty_portfolio() {
+        let portfolio = Portfolio::new(&[], &[], &ticker(), "EUR");
+        assert_eq!(portfolio.total_value(), Decimal::ZERO);
+        assert!(portfolio.allocation().is_empty());
+    }
+
+    fn ticker() -> Ticker {
+        let mut ticker = Ticker::default();
+        for (symbol, price) in [("BTC", dec!(20000)), ("ETH", dec!(1500)), ("XAU", dec!(50))] {
+            ticker.prices.insert(
+                symbol.to_string(),
+                HashMap::from([("EUR".to_string(), price)]),
+            );
+        }
+        ticker
+    }
+
+    fn asset_wallet(
+        symbol: &str,
+        class: AssetClass,
+        balance: Decimal,
+        deleted: bool,
+    ) -> AssetWallet {
+        AssetWallet {
+            asset_id: format!("{symbol}-id"),
+            asset_symbol: symbol.to_string(),
+            balance,
+            class,
+            deleted,
+            id: format!("{symbol}-wallet"),
+            is_default: true,
+            name: format!("{symbol} wallet"),
+        }
+    }
+
+    fn fiat_wallet(symbol: &str, balance: Decimal) -> FiatWallet {
+        FiatWallet {
+            balance,
+            fiat_id: format!("{symbol}-id"),
+            id: format!("{symbol}-wallet"),
+            name: format!("{symbol} wallet"),
+            pending_transactions_count: 0,
+            symbol: symbol.to_string(),
+        }
+    }
+}
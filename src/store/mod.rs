@@ -0,0 +1,785 @@
+//! # Store
+//!
+//! Local SQLite persistence of the Bitpanda history, available with the `store` feature.
+//!
+//! Each model has its own table keyed by its Bitpanda id; OHLC candles are keyed by asset pid, currency,
+//! period and time. Decimals and dates are stored as TEXT, to preserve their precision and offset, while
+//! enums are stored with their serde representation.
+//!
+//! [`Store::sync`] keeps the database up to date with the account, downloading only the new records.
+
+mod schema;
+mod sync;
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, Row};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::model::crypto_wallet::CryptoWalletTransaction;
+use crate::model::fiat_wallet::FiatWalletTransaction;
+use crate::model::ohlc::{Ohlc, Period};
+use crate::model::{Asset, AssetWallet, CryptoWallet, FiatWallet, OpenHighLowCloseChart, Trade};
+use crate::ApiError;
+
+pub use sync::SyncReport;
+
+/// Store result
+pub type StoreResult<T> = Result<T, StoreError>;
+
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error("API error: {0}")]
+    Api(ApiError),
+    #[error("SQLite error: {0}")]
+    Sqlite(rusqlite::Error),
+}
+
+impl From<ApiError> for StoreError {
+    fn from(value: ApiError) -> Self {
+        Self::Api(value)
+    }
+}
+
+impl From<rusqlite::Error> for StoreError {
+    fn from(value: rusqlite::Error) -> Self {
+        Self::Sqlite(value)
+    }
+}
+
+/// SQLite database storing the Bitpanda history
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Open the database at `path`, creating it if it doesn't exist
+    pub fn open(path: impl AsRef<Path>) -> StoreResult<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Open a database in memory
+    pub fn open_in_memory() -> StoreResult<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> StoreResult<Self> {
+        schema::migrate(&conn)?;
+
+        Ok(Self { conn })
+    }
+
+    /// Insert or update assets
+    pub fn upsert_assets(&mut self, assets: &[Asset]) -> StoreResult<()> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO assets (id, pid, name, symbol, type) VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for asset in assets {
+                stmt.execute(params![
+                    asset.id,
+                    asset.pid,
+                    asset.name,
+                    asset.symbol,
+                    to_sql(&asset.r#type)?,
+                ])?;
+            }
+        }
+
+        tx.commit().map_err(Into::into)
+    }
+
+    /// Get the stored assets
+    pub fn assets(&self) -> StoreResult<Vec<Asset>> {
+        self.query(
+            "SELECT id, pid, name, symbol, type FROM assets ORDER BY id",
+            |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    pid: row.get(1)?,
+                    name: row.get(2)?,
+                    symbol: row.get(3)?,
+                    r#type: from_sql(row, 4)?,
+                })
+            },
+        )
+    }
+
+    /// Insert or update asset wallets
+    pub fn upsert_asset_wallets(&mut self, wallets: &[AssetWallet]) -> StoreResult<()> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO asset_wallets (id, asset_id, asset_symbol, balance, class, deleted, is_default, name)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for wallet in wallets {
+                stmt.execute(params![
+                    wallet.id,
+                    wallet.asset_id,
+                    wallet.asset_symbol,
+                    wallet.balance.to_string(),
+                    to_sql(&wallet.class)?,
+                    wallet.deleted,
+                    wallet.is_default,
+                    wallet.name,
+                ])?;
+            }
+        }
+
+        tx.commit().map_err(Into::into)
+    }
+
+    /// Get the stored asset wallets
+    pub fn asset_wallets(&self) -> StoreResult<Vec<AssetWallet>> {
+        self.query(
+            "SELECT id, asset_id, asset_symbol, balance, class, deleted, is_default, name FROM asset_wallets ORDER BY id",
+            |row| {
+                Ok(AssetWallet {
+                    id: row.get(0)?,
+                    asset_id: row.get(1)?,
+                    asset_symbol: row.get(2)?,
+                    balance: parse(row, 3)?,
+                    class: from_sql(row, 4)?,
+                    deleted: row.get(5)?,
+                    is_default: row.get(6)?,
+                    name: row.get(7)?,
+                })
+            },
+        )
+    }
+
+    /// Insert or update crypto wallets
+    pub fn upsert_crypto_wallets(&mut self, wallets: &[CryptoWallet]) -> StoreResult<()> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO crypto_wallets (id, balance, cryptocoin_id, deleted, is_default, name, pending_transactions_count, symbol)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for wallet in wallets {
+                stmt.execute(params![
+                    wallet.id,
+                    wallet.balance.to_string(),
+                    wallet.cryptocoin_id,
+                    wallet.deleted,
+                    wallet.is_default,
+                    wallet.name,
+                    wallet.pending_transactions_count,
+                    wallet.symbol,
+                ])?;
+            }
+        }
+
+        tx.commit().map_err(Into::into)
+    }
+
+    /// Get the stored crypto wallets
+    pub fn crypto_wallets(&self) -> StoreResult<Vec<CryptoWallet>> {
+        self.query(
+            "SELECT id, balance, cryptocoin_id, deleted, is_default, name, pending_transactions_count, symbol FROM crypto_wallets ORDER BY id",
+            |row| {
+                Ok(CryptoWallet {
+                    id: row.get(0)?,
+                    balance: parse(row, 1)?,
+                    cryptocoin_id: row.get(2)?,
+                    deleted: row.get(3)?,
+                    is_default: row.get(4)?,
+                    name: row.get(5)?,
+                    pending_transactions_count: row.get(6)?,
+                    symbol: row.get(7)?,
+                })
+            },
+        )
+    }
+
+    /// Insert or update fiat wallets
+    pub fn upsert_fiat_wallets(&mut self, wallets: &[FiatWallet]) -> StoreResult<()> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO fiat_wallets (id, balance, fiat_id, name, pending_transactions_count, symbol)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for wallet in wallets {
+                stmt.execute(params![
+                    wallet.id,
+                    wallet.balance.to_string(),
+                    wallet.fiat_id,
+                    wallet.name,
+                    wallet.pending_transactions_count,
+                    wallet.symbol,
+                ])?;
+            }
+        }
+
+        tx.commit().map_err(Into::into)
+    }
+
+    /// Get the stored fiat wallets
+    pub fn fiat_wallets(&self) -> StoreResult<Vec<FiatWallet>> {
+        self.query(
+            "SELECT id, balance, fiat_id, name, pending_transactions_count, symbol FROM fiat_wallets ORDER BY id",
+            |row| {
+                Ok(FiatWallet {
+                    id: row.get(0)?,
+                    balance: parse(row, 1)?,
+                    fiat_id: row.get(2)?,
+                    name: row.get(3)?,
+                    pending_transactions_count: row.get(4)?,
+                    symbol: row.get(5)?,
+                })
+            },
+        )
+    }
+
+    /// Insert or update trades.
+    /// The legs of the swaps are stored as trades too, referenced by their id
+    pub fn upsert_trades(&mut self, trades: &[Trade]) -> StoreResult<()> {
+        let tx = self.conn.transaction()?;
+        {
+            // the related leg doesn't reference back its trade, so the reference must be kept if known
+            let mut stmt = tx.prepare(
+                "INSERT INTO trades (id, amount_asset, amount_fiat, datetime, fiat_to_eur_rate, fiat_wallet_id, id_asset, id_fiat, id_wallet, price, related_swap_trade_id, status, symbol, type)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+                ON CONFLICT (id) DO UPDATE SET
+                    amount_asset = excluded.amount_asset,
+                    amount_fiat = excluded.amount_fiat,
+                    datetime = excluded.datetime,
+                    fiat_to_eur_rate = excluded.fiat_to_eur_rate,
+                    fiat_wallet_id = excluded.fiat_wallet_id,
+                    id_asset = excluded.id_asset,
+                    id_fiat = excluded.id_fiat,
+                    id_wallet = excluded.id_wallet,
+                    price = excluded.price,
+                    related_swap_trade_id = COALESCE(excluded.related_swap_trade_id, trades.related_swap_trade_id),
+                    status = excluded.status,
+                    symbol = excluded.symbol,
+                    type = excluded.type",
+            )?;
+            let related = trades
+                .iter()
+                .filter_map(|trade| trade.related_swap_trade.as_deref());
+            for trade in related.chain(trades.iter()) {
+                stmt.execute(params![
+                    trade.id,
+                    trade.amount_asset.to_string(),
+                    trade.amount_fiat.to_string(),
+                    trade.datetime.to_rfc3339(),
+                    trade.fiat_to_eur_rate.to_string(),
+                    trade.fiat_wallet_id,
+                    trade.id_asset,
+                    trade.id_fiat,
+                    trade.id_wallet,
+                    trade.price.to_string(),
+                    trade.related_swap_trade.as_ref().map(|related| &related.id),
+                    to_sql(&trade.status)?,
+                    trade.symbol,
+                    to_sql(&trade.r#type)?,
+                ])?;
+            }
+        }
+
+        tx.commit().map_err(Into::into)
+    }
+
+    /// Get the stored trades, from the most recent, as returned by the API.
+    /// The related swap trade is resolved without its own related trade
+    pub fn trades(&self) -> StoreResult<Vec<Trade>> {
+        let rows = self.query(
+            "SELECT id, amount_asset, amount_fiat, datetime, fiat_to_eur_rate, fiat_wallet_id, id_asset, id_fiat, id_wallet, price, related_swap_trade_id, status, symbol, type FROM trades",
+            |row| {
+                let trade = Trade {
+                    id: row.get(0)?,
+                    amount_asset: parse(row, 1)?,
+                    amount_fiat: parse(row, 2)?,
+                    datetime: parse(row, 3)?,
+                    fiat_to_eur_rate: parse(row, 4)?,
+                    fiat_wallet_id: row.get(5)?,
+                    id_asset: row.get(6)?,
+                    id_fiat: row.get(7)?,
+                    id_wallet: row.get(8)?,
+                    price: parse(row, 9)?,
+                    related_swap_trade: None,
+                    status: from_sql(row, 11)?,
+                    symbol: row.get(12)?,
+                    r#type: from_sql(row, 13)?,
+                };
+                Ok((trade, row.get::<_, Option<String>>(10)?))
+            },
+        )?;
+        let by_id: HashMap<&str, &Trade> = rows
+            .iter()
+            .map(|(trade, _)| (trade.id.as_str(), trade))
+            .collect();
+        let mut trades: Vec<Trade> = rows
+            .iter()
+            .map(|(trade, related_id)| Trade {
+                related_swap_trade: related_id
+                    .as_deref()
+                    .and_then(|id| by_id.get(id))
+                    .map(|related| Box::new((*related).clone())),
+                ..trade.clone()
+            })
+            .collect();
+        trades.sort_by(|a, b| b.datetime.cmp(&a.datetime).then_with(|| a.id.cmp(&b.id)));
+
+        Ok(trades)
+    }
+
+    /// Insert or update crypto wallet transactions
+    pub fn upsert_crypto_wallet_transactions(
+        &mut self,
+        transactions: &[CryptoWalletTransaction],
+    ) -> StoreResult<()> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO crypto_wallet_transactions (id, amount_eur, amount, confirmations, cryptocoin_id, current_fiat_amount, current_fiat_id, datetime, fee, in_or_out, recipient, status, transaction_type, wallet_id)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            )?;
+            for transaction in transactions {
+                stmt.execute(params![
+                    transaction.id,
+                    transaction.amount_eur.to_string(),
+                    transaction.amount.to_string(),
+                    transaction.confirmations,
+                    transaction.cryptocoin_id,
+                    transaction.current_fiat_amount.to_string(),
+                    transaction.current_fiat_id,
+                    transaction.datetime.to_rfc3339(),
+                    transaction.fee.to_string(),
+                    to_sql(&transaction.in_or_out)?,
+                    transaction.recipient,
+                    to_sql(&transaction.status)?,
+                    to_sql(&transaction.transaction_type)?,
+                    transaction.wallet_id,
+                ])?;
+            }
+        }
+
+        tx.commit().map_err(Into::into)
+    }
+
+    /// Get the stored crypto wallet transactions, from the most recent
+    pub fn crypto_wallet_transactions(&self) -> StoreResult<Vec<CryptoWalletTransaction>> {
+        let mut transactions = self.query(
+            "SELECT id, amount_eur, amount, confirmations, cryptocoin_id, current_fiat_amount, current_fiat_id, datetime, fee, in_or_out, recipient, status, transaction_type, wallet_id FROM crypto_wallet_transactions",
+            |row| {
+                Ok(CryptoWalletTransaction {
+                    id: row.get(0)?,
+                    amount_eur: parse(row, 1)?,
+                    amount: parse(row, 2)?,
+                    confirmations: row.get(3)?,
+                    cryptocoin_id: row.get(4)?,
+                    current_fiat_amount: parse(row, 5)?,
+                    current_fiat_id: row.get(6)?,
+                    datetime: parse(row, 7)?,
+                    fee: parse(row, 8)?,
+                    in_or_out: from_sql(row, 9)?,
+                    recipient: row.get(10)?,
+                    status: from_sql(row, 11)?,
+                    transaction_type: from_sql(row, 12)?,
+                    wallet_id: row.get(13)?,
+                })
+            },
+        )?;
+        transactions.sort_by(|a, b| b.datetime.cmp(&a.datetime).then_with(|| a.id.cmp(&b.id)));
+
+        Ok(transactions)
+    }
+
+    /// Insert or update fiat wallet transactions
+    pub fn upsert_fiat_wallet_transactions(
+        &mut self,
+        transactions: &[FiatWalletTransaction],
+    ) -> StoreResult<()> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO fiat_wallet_transactions (id, amount, datetime, fee, fiat_id, in_or_out, status, to_eur_rate, transaction_type, user_id, wallet_id)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )?;
+            for transaction in transactions {
+                stmt.execute(params![
+                    transaction.id,
+                    transaction.amount.to_string(),
+                    transaction.datetime.to_rfc3339(),
+                    transaction.fee.to_string(),
+                    transaction.fiat_id,
+                    to_sql(&transaction.in_or_out)?,
+                    to_sql(&transaction.status)?,
+                    transaction.to_eur_rate.to_string(),
+                    to_sql(&transaction.transaction_type)?,
+                    transaction.user_id,
+                    transaction.wallet_id,
+                ])?;
+            }
+        }
+
+        tx.commit().map_err(Into::into)
+    }
+
+    /// Get the stored fiat wallet transactions, from the most recent
+    pub fn fiat_wallet_transactions(&self) -> StoreResult<Vec<FiatWalletTransaction>> {
+        let mut transactions = self.query(
+            "SELECT id, amount, datetime, fee, fiat_id, in_or_out, status, to_eur_rate, transaction_type, user_id, wallet_id FROM fiat_wallet_transactions",
+            |row| {
+                Ok(FiatWalletTransaction {
+                    id: row.get(0)?,
+                    amount: parse(row, 1)?,
+                    datetime: parse(row, 2)?,
+                    fee: parse(row, 3)?,
+                    fiat_id: row.get(4)?,
+                    in_or_out: from_sql(row, 5)?,
+                    status: from_sql(row, 6)?,
+                    to_eur_rate: parse(row, 7)?,
+                    transaction_type: from_sql(row, 8)?,
+                    user_id: row.get(9)?,
+                    wallet_id: row.get(10)?,
+                })
+            },
+        )?;
+        transactions.sort_by(|a, b| b.datetime.cmp(&a.datetime).then_with(|| a.id.cmp(&b.id)));
+
+        Ok(transactions)
+    }
+
+    /// Insert or update the candles of the OHLC chart of the asset with `pid` in `currency`
+    pub fn upsert_ohlc(
+        &mut self,
+        pid: &str,
+        currency: &str,
+        chart: &OpenHighLowCloseChart,
+    ) -> StoreResult<()> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO ohlc (pid, currency, period, time, open, high, low, close)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for candle in &chart.chart {
+                stmt.execute(params![
+                    pid,
+                    currency,
+                    to_sql(&chart.period)?,
+                    candle.time.to_rfc3339(),
+                    candle.open.to_string(),
+                    candle.high.to_string(),
+                    candle.low.to_string(),
+                    candle.close.to_string(),
+                ])?;
+            }
+        }
+
+        tx.commit().map_err(Into::into)
+    }
+
+    /// Get the stored OHLC chart of the asset with `pid` in `currency`, ordered by time.
+    /// Returns `None` if no candle is stored
+    pub fn ohlc(
+        &self,
+        pid: &str,
+        currency: &str,
+        period: Period,
+    ) -> StoreResult<Option<OpenHighLowCloseChart>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT time, open, high, low, close FROM ohlc WHERE pid = ?1 AND currency = ?2 AND period = ?3",
+        )?;
+        let mut chart = stmt
+            .query_map(params![pid, currency, to_sql(&period)?], |row| {
+                Ok(Ohlc {
+                    time: parse(row, 0)?,
+                    open: parse(row, 1)?,
+                    high: parse(row, 2)?,
+                    low: parse(row, 3)?,
+                    close: parse(row, 4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        if chart.is_empty() {
+            return Ok(None);
+        }
+        chart.sort_by_key(|candle| candle.time);
+
+        Ok(Some(OpenHighLowCloseChart { chart, period }))
+    }
+
+    fn query<T, F>(&self, sql: &str, f: F) -> StoreResult<Vec<T>>
+    where
+        F: FnMut(&Row<'_>) -> rusqlite::Result<T>,
+    {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map([], f)?;
+
+        rows.collect::<rusqlite::Result<Vec<T>>>()
+            .map_err(Into::into)
+    }
+}
+
+/// Enums are stored with their serde representation, which must be a string
+fn to_sql<T: Serialize>(value: &T) -> rusqlite::Result<String> {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => Ok(s),
+        Ok(value) => Err(rusqlite::Error::ToSqlConversionFailure(
+            format!("expected a string, got {value}").into(),
+        )),
+        Err(err) => Err(rusqlite::Error::ToSqlConversionFailure(Box::new(err))),
+    }
+}
+
+fn from_sql<T: DeserializeOwned>(row: &Row<'_>, idx: usize) -> rusqlite::Result<T> {
+    let value: String = row.get(idx)?;
+    serde_json::from_value(serde_json::Value::String(value))
+        .map_err(|err| rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, Box::new(err)))
+}
+
+/// Parse a TEXT column
+fn parse<T>(row: &Row<'_>, idx: usize) -> rusqlite::Result<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let value: String = row.get(idx)?;
+    value
+        .parse()
+        .map_err(|err| rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, Box::new(err)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::slice;
+
+    use pretty_assertions::assert_eq;
+    use rust_decimal_macros::dec;
+
+    use crate::model::{
+        AssetClass, InOrOut, TradeStatus, TradeType, TransactionStatus, TransactionType,
+    };
+    use crate::testing::fixture::{datetime, TradeBuilder};
+
+    #[test]
+    fn should_migrate_existing_database() {
+        let tmp = std::env::temp_dir().join(format!("bitpanda-store-{}.db", fastrand::u64(..)));
+        let mut store = Store::open(&tmp).unwrap();
+        store.upsert_assets(&[asset()]).unwrap();
+        drop(store);
+        let store = Store::open(&tmp).unwrap();
+        assert_eq!(store.assets().unwrap(), vec![asset()]);
+        std::fs::remove_file(tmp).unwrap();
+    }
+
+    #[test]
+    fn should_store_assets_and_wallets() {
+        let mut store = Store::open_in_memory().unwrap();
+        let asset_wallet = AssetWallet {
+            asset_id: "28".to_string(),
+            asset_symbol: "XAU".to_string(),
+            balance: dec!(1.50),
+            class: AssetClass::Metal,
+            deleted: false,
+            id: "gold-wallet".to_string(),
+            is_default: true,
+            name: "Gold".to_string(),
+        };
+        let crypto_wallet = CryptoWallet {
+            balance: dec!(0.00124),
+            cryptocoin_id: "1".to_string(),
+            deleted: false,
+            id: "btc-wallet".to_string(),
+            is_default: true,
+            name: "BTC Wallet".to_string(),
+            pending_transactions_count: 2,
+            symbol: "BTC".to_string(),
+        };
+        let fiat_wallet = FiatWallet {
+            balance: dec!(100.50),
+            fiat_id: "1".to_string(),
+            id: "eur-wallet".to_string(),
+            name: "EUR Wallet".to_string(),
+            pending_transactions_count: 0,
+            symbol: "EUR".to_string(),
+        };
+        store.upsert_assets(&[asset()]).unwrap();
+        store
+            .upsert_asset_wallets(slice::from_ref(&asset_wallet))
+            .unwrap();
+        store
+            .upsert_crypto_wallets(slice::from_ref(&crypto_wallet))
+            .unwrap();
+        store
+            .upsert_fiat_wallets(slice::from_ref(&fiat_wallet))
+            .unwrap();
+        assert_eq!(store.assets().unwrap(), vec![asset()]);
+        assert_eq!(store.asset_wallets().unwrap(), vec![asset_wallet]);
+        assert_eq!(store.crypto_wallets().unwrap(), vec![crypto_wallet]);
+        assert_eq!(store.fiat_wallets().unwrap(), vec![fiat_wallet]);
+    }
+
+    #[test]
+    fn should_store_trades_with_swap_legs() {
+        let mut store = Store::open_in_memory().unwrap();
+        let buy = TradeBuilder::new("buy")
+            .datetime(datetime("2023-02-01T10:00:00+01:00"))
+            .build();
+        let sell = TradeBuilder::new("sell")
+            .r#type(TradeType::Sell)
+            .datetime(datetime("2023-02-01T10:00:00+01:00"))
+            .swap(buy.clone())
+            .build();
+        let older = TradeBuilder::new("older")
+            .datetime(datetime("2023-01-01T10:00:00+01:00"))
+            .build();
+        store.upsert_trades(&[sell.clone(), older.clone()]).unwrap();
+        assert_eq!(
+            store.trades().unwrap(),
+            vec![buy.clone(), sell.clone(), older.clone()]
+        );
+
+        // the leg is listed with its own reference, which must not be overwritten by the embedded one
+        let buy = Trade {
+            related_swap_trade: Some(Box::new(Trade {
+                related_swap_trade: None,
+                ..sell.clone()
+            })),
+            ..buy
+        };
+        store.upsert_trades(slice::from_ref(&buy)).unwrap();
+        store.upsert_trades(slice::from_ref(&sell)).unwrap();
+        assert_eq!(store.trades().unwrap(), vec![buy, sell, older]);
+    }
+
+    #[test]
+    fn should_store_enums_as_strings() {
+        assert_eq!(to_sql(&TradeType::Buy).unwrap(), "buy");
+        assert_eq!(
+            to_sql(&InOrOut::Unknown("internal".to_string())).unwrap(),
+            "internal"
+        );
+        assert!(matches!(
+            to_sql(&1),
+            Err(rusqlite::Error::ToSqlConversionFailure(_))
+        ));
+    }
+
+    #[test]
+    fn should_update_trades() {
+        let mut store = Store::open_in_memory().unwrap();
+        let pending = TradeBuilder::new("trade")
+            .status(TradeStatus::Pending)
+            .datetime(datetime("2023-01-01T10:00:00+01:00"))
+            .build();
+        store.upsert_trades(slice::from_ref(&pending)).unwrap();
+        let finished = Trade {
+            status: TradeStatus::Finished,
+            ..pending
+        };
+        store.upsert_trades(slice::from_ref(&finished)).unwrap();
+        assert_eq!(store.trades().unwrap(), vec![finished]);
+    }
+
+    #[test]
+    fn should_store_transactions() {
+        let mut store = Store::open_in_memory().unwrap();
+        let crypto = CryptoWalletTransaction {
+            amount_eur: dec!(55.00),
+            amount: dec!(0.05),
+            confirmations: 10,
+            cryptocoin_id: "5".to_string(),
+            current_fiat_amount: dec!(60),
+            current_fiat_id: "1".to_string(),
+            datetime: "2023-03-01T08:00:00+01:00".parse().unwrap(),
+            fee: dec!(0.001),
+            id: "crypto-tx".to_string(),
+            in_or_out: InOrOut::Outgoing,
+            recipient: "0xabc".to_string(),
+            status: TransactionStatus::UnconfirmedTransactionOut,
+            transaction_type: TransactionType::Withdrawal,
+            wallet_id: "eth-wallet".to_string(),
+        };
+        let fiat = FiatWalletTransaction {
+            amount: dec!(1000),
+            datetime: "2023-01-02T12:00:00+01:00".parse().unwrap(),
+            fee: dec!(0),
+            fiat_id: "1".to_string(),
+            id: "fiat-tx".to_string(),
+            in_or_out: InOrOut::Incoming,
+            status: TransactionStatus::Finished,
+            to_eur_rate: dec!(1),
+            transaction_type: TransactionType::Deposit,
+            user_id: "user".to_string(),
+            wallet_id: "eur-wallet".to_string(),
+        };
+        store
+            .upsert_crypto_wallet_transactions(slice::from_ref(&crypto))
+            .unwrap();
+        store
+            .upsert_fiat_wallet_transactions(slice::from_ref(&fiat))
+            .unwrap();
+        assert_eq!(store.crypto_wallet_transactions().unwrap(), vec![crypto]);
+        assert_eq!(store.fiat_wallet_transactions().unwrap(), vec![fiat]);
+    }
+
+    #[test]
+    fn should_store_ohlc() {
+        let mut store = Store::open_in_memory().unwrap();
+        let candle = |time: &str, close| Ohlc {
+            close,
+            high: dec!(21500),
+            low: dec!(20500),
+            open: dec!(20800.25),
+            time: time.parse().unwrap(),
+        };
+        let chart = OpenHighLowCloseChart {
+            chart: vec![
+                candle("2023-01-11T00:00:00+00:00", dec!(21000)),
+                candle("2023-01-10T00:00:00+00:00", dec!(20900)),
+            ],
+            period: Period::Day,
+        };
+        store.upsert_ohlc("btc-pid", "EUR", &chart).unwrap();
+        store
+            .upsert_ohlc(
+                "btc-pid",
+                "EUR",
+                &OpenHighLowCloseChart {
+                    chart: vec![candle("2023-01-11T00:00:00+00:00", dec!(21100))],
+                    period: Period::Day,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            store.ohlc("btc-pid", "EUR", Period::Day).unwrap(),
+            Some(OpenHighLowCloseChart {
+                chart: vec![
+                    candle("2023-01-10T00:00:00+00:00", dec!(20900)),
+                    candle("2023-01-11T00:00:00+00:00", dec!(21100)),
+                ],
+                period: Period::Day,
+            })
+        );
+        assert!(store.ohlc("btc-pid", "USD", Period::Day).unwrap().is_none());
+        assert!(store
+            .ohlc("btc-pid", "EUR", Period::Week)
+            .unwrap()
+            .is_none());
+    }
+
+    fn asset() -> Asset {
+        Asset {
+            id: "1".to_string(),
+            pid: "btc-pid".to_string(),
+            name: "Bitcoin".to_string(),
+            symbol: "BTC".to_string(),
+            r#type: AssetClass::Cryptocurrency,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! # Schema
+//!
+//! Database schema and its migrations
+
+use rusqlite::Connection;
+
+/// Migrations to apply, in order; the schema version is the number of migrations applied
+const MIGRATIONS: &[&str] = &[r#"
+    CREATE TABLE assets (
+        id TEXT PRIMARY KEY NOT NULL,
+        pid TEXT NOT NULL,
+        name TEXT NOT NULL,
+        symbol TEXT NOT NULL,
+        type TEXT NOT NULL
+    );
+    CREATE TABLE asset_wallets (
+        id TEXT PRIMARY KEY NOT NULL,
+        asset_id TEXT NOT NULL,
+        asset_symbol TEXT NOT NULL,
+        balance TEXT NOT NULL,
+        class TEXT NOT NULL,
+        deleted INTEGER NOT NULL,
+        is_default INTEGER NOT NULL,
+        name TEXT NOT NULL
+    );
+    CREATE TABLE crypto_wallets (
+        id TEXT PRIMARY KEY NOT NULL,
+        balance TEXT NOT NULL,
+        cryptocoin_id TEXT NOT NULL,
+        deleted INTEGER NOT NULL,
+        is_default INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        pending_transactions_count INTEGER NOT NULL,
+        symbol TEXT NOT NULL
+    );
+    CREATE TABLE fiat_wallets (
+        id TEXT PRIMARY KEY NOT NULL,
+        balance TEXT NOT NULL,
+        fiat_id TEXT NOT NULL,
+        name TEXT NOT NULL,
+        pending_transactions_count INTEGER NOT NULL,
+        symbol TEXT NOT NULL
+    );
+    CREATE TABLE trades (
+        id TEXT PRIMARY KEY NOT NULL,
+        amount_asset TEXT NOT NULL,
+        amount_fiat TEXT NOT NULL,
+        datetime TEXT NOT NULL,
+        fiat_to_eur_rate TEXT NOT NULL,
+        fiat_wallet_id TEXT,
+        id_asset TEXT NOT NULL,
+        id_fiat TEXT NOT NULL,
+        id_wallet TEXT NOT NULL,
+        price TEXT NOT NULL,
+        related_swap_trade_id TEXT,
+        status TEXT NOT NULL,
+        symbol TEXT NOT NULL,
+        type TEXT NOT NULL
+    );
+    CREATE TABLE crypto_wallet_transactions (
+        id TEXT PRIMARY KEY NOT NULL,
+        amount_eur TEXT NOT NULL,
+        amount TEXT NOT NULL,
+        confirmations INTEGER NOT NULL,
+        cryptocoin_id TEXT NOT NULL,
+        current_fiat_amount TEXT NOT NULL,
+        current_fiat_id TEXT NOT NULL,
+        datetime TEXT NOT NULL,
+        fee TEXT NOT NULL,
+        in_or_out TEXT NOT NULL,
+        recipient TEXT NOT NULL,
+        status TEXT NOT NULL,
+        transaction_type TEXT NOT NULL,
+        wallet_id TEXT NOT NULL
+    );
+    CREATE TABLE fiat_wallet_transactions (
+        id TEXT PRIMARY KEY NOT NULL,
+        amount TEXT NOT NULL,
+        datetime TEXT NOT NULL,
+        fee TEXT NOT NULL,
+        fiat_id TEXT NOT NULL,
+        in_or_out TEXT NOT NULL,
+        status TEXT NOT NULL,
+        to_eur_rate TEXT NOT NULL,
+        transaction_type TEXT NOT NULL,
+        user_id TEXT NOT NULL,
+        wallet_id TEXT NOT NULL
+    );
+    CREATE TABLE ohlc (
+        pid TEXT NOT NULL,
+        currency TEXT NOT NULL,
+        period TEXT NOT NULL,
+        time TEXT NOT NULL,
+        open TEXT NOT NULL,
+        high TEXT NOT NULL,
+        low TEXT NOT NULL,
+        close TEXT NOT NULL,
+        PRIMARY KEY (pid, currency, period, time)
+    );
+"#];
+
+/// Apply the migrations which haven't been applied yet
+pub fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        debug!("applying store migration {}", i + 1);
+        conn.execute_batch(&format!(
+            "BEGIN; {migration} PRAGMA user_version = {}; COMMIT;",
+            i + 1
+        ))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,220 @@
+//! # Sync
+//!
+//! Synchronization of the store with the Bitpanda account
+
+use super::{Store, StoreResult};
+use crate::api::HistoryItem;
+use crate::model::ohlc::Period;
+use crate::model::{AssetClass, TradeStatus, TransactionStatus};
+use crate::{Client, TradeFilter, TransactionFilter};
+
+/// Number of records upserted by [`Store::sync`]
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct SyncReport {
+    pub asset_wallets: usize,
+    pub crypto_wallets: usize,
+    pub fiat_wallets: usize,
+    pub trades: usize,
+    pub crypto_wallet_transactions: usize,
+    pub fiat_wallet_transactions: usize,
+}
+
+impl Store {
+    /// Synchronize the wallets, the trades and the wallet transactions with the account.
+    ///
+    /// Wallets are always downloaded, while trades and transactions are downloaded starting from the most recent
+    /// stored record which is older than any stored record still pending, so that their updates are not lost.
+    pub async fn sync(&mut self, client: &Client) -> StoreResult<SyncReport> {
+        let asset_wallets = client.get_asset_wallets().await?;
+        self.upsert_asset_wallets(&asset_wallets)?;
+        let crypto_wallets = client.get_crypto_wallets().await?;
+        self.upsert_crypto_wallets(&crypto_wallets)?;
+        let fiat_wallets = client.get_fiat_wallets().await?;
+        self.upsert_fiat_wallets(&fiat_wallets)?;
+
+        let mut filter = TradeFilter::default();
+        if let Some(id) = cursor(&self.trades()?, |trade| {
+            matches!(trade.status, TradeStatus::Finished | TradeStatus::Canceled)
+        }) {
+            filter = filter.since_id(id);
+        }
+        let trades = client.get_trades_with_filter(filter).await?;
+        self.upsert_trades(&trades)?;
+
+        let mut filter = TransactionFilter::default();
+        if let Some(id) = cursor(&self.crypto_wallet_transactions()?, |tx| {
+            is_final(&tx.status)
+        }) {
+            filter = filter.since_id(id);
+        }
+        let crypto_wallet_transactions = client
+            .get_crypto_wallet_transactions_with_filter(filter)
+            .await?;
+        self.upsert_crypto_wallet_transactions(&crypto_wallet_transactions)?;
+
+        let mut filter = TransactionFilter::default();
+        if let Some(id) = cursor(&self.fiat_wallet_transactions()?, |tx| is_final(&tx.status)) {
+            filter = filter.since_id(id);
+        }
+        let fiat_wallet_transactions = client
+            .get_fiat_wallet_transactions_with_filter(filter)
+            .await?;
+        self.upsert_fiat_wallet_transactions(&fiat_wallet_transactions)?;
+
+        let report = SyncReport {
+            asset_wallets: asset_wallets.len(),
+            crypto_wallets: crypto_wallets.len(),
+            fiat_wallets: fiat_wallets.len(),
+            trades: trades.len(),
+            crypto_wallet_transactions: crypto_wallet_transactions.len(),
+            fiat_wallet_transactions: fiat_wallet_transactions.len(),
+        };
+        debug!("store synced: {report:?}");
+
+        Ok(report)
+    }
+
+    /// Synchronize the assets of `class`. Returns the number of assets upserted
+    pub async fn sync_assets(&mut self, client: &Client, class: AssetClass) -> StoreResult<usize> {
+        let assets = client.get_assets(class).await?;
+        self.upsert_assets(&assets)?;
+
+        Ok(assets.len())
+    }
+
+    /// Synchronize the OHLC chart of the asset with `pid` in `currency`. Returns the number of candles upserted
+    pub async fn sync_ohlc(
+        &mut self,
+        client: &Client,
+        period: Period,
+        pid: &str,
+        currency: &str,
+    ) -> StoreResult<usize> {
+        let chart = client.get_ohlc(period, pid, currency).await?;
+        self.upsert_ohlc(pid, currency, &chart)?;
+
+        Ok(chart.chart.len())
+    }
+}
+
+fn is_final(status: &TransactionStatus) -> bool {
+    matches!(
+        status,
+        TransactionStatus::Finished | TransactionStatus::Canceled
+    )
+}
+
+/// Get the id to sync from, given the stored `items` from the most recent.
+/// Returns `None` if the whole history must be downloaded
+fn cursor<T: HistoryItem>(items: &[T], is_final: impl Fn(&T) -> bool) -> Option<String> {
+    let first_final = match items.iter().rposition(|item| !is_final(item)) {
+        Some(oldest_pending) => oldest_pending + 1,
+        None => 0,
+    };
+
+    items.get(first_final).map(|item| item.id().to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::model::crypto_wallet::CryptoWalletTransaction;
+    use crate::model::{InOrOut, TradeType, TransactionType};
+    use crate::testing::fixture::{self, TradeBuilder};
+    use crate::testing::MockServer;
+
+    #[tokio::test]
+    async fn should_sync_new_and_pending_records() {
+        let server = MockServer::start().await;
+        let mut trades = fixture::trades(30);
+        trades[1].status = TradeStatus::Pending;
+        server.set_trades(trades.clone());
+        server.set_crypto_wallet_transactions(fixture::crypto_wallet_transactions(10));
+        let client = server.client();
+        let mut store = Store::open_in_memory().unwrap();
+
+        let report = store.sync(&client).await.unwrap();
+        assert_eq!(report.trades, 30);
+        assert_eq!(report.crypto_wallet_transactions, 10);
+        assert_eq!(report.fiat_wallet_transactions, 0);
+
+        // a new trade is executed and the pending one is finished
+        trades[1].status = TradeStatus::Finished;
+        trades.insert(
+            0,
+            TradeBuilder::new("trade-new")
+                .r#type(TradeType::Sell)
+                .datetime(trades[0].datetime + chrono::Duration::hours(1))
+                .build(),
+        );
+        server.set_trades(trades.clone());
+        let report = store.sync(&client).await.unwrap();
+        assert_eq!(report.trades, 3);
+        assert_eq!(report.crypto_wallet_transactions, 0);
+        assert_eq!(store.trades().unwrap(), trades);
+
+        let report = store.sync(&client).await.unwrap();
+        assert_eq!(report.trades, 0);
+    }
+
+    #[test]
+    fn should_sync_from_most_recent_record() {
+        let items = vec![
+            tx("3", TransactionStatus::Finished),
+            tx("2", TransactionStatus::Finished),
+        ];
+        assert_eq!(
+            cursor(&items, |tx| is_final(&tx.status)).as_deref(),
+            Some("3")
+        );
+    }
+
+    #[test]
+    fn should_sync_from_record_older_than_pending() {
+        let items = vec![
+            tx("4", TransactionStatus::Finished),
+            tx("3", TransactionStatus::Pending),
+            tx("2", TransactionStatus::Finished),
+            tx("1", TransactionStatus::Finished),
+        ];
+        assert_eq!(
+            cursor(&items, |tx| is_final(&tx.status)).as_deref(),
+            Some("2")
+        );
+    }
+
+    #[test]
+    fn should_sync_everything() {
+        assert_eq!(
+            cursor::<CryptoWalletTransaction>(&[], |tx| is_final(&tx.status)),
+            None
+        );
+        let items = vec![
+            tx("2", TransactionStatus::Finished),
+            tx("1", TransactionStatus::Unconfirmed),
+        ];
+        assert_eq!(cursor(&items, |tx| is_final(&tx.status)), None);
+    }
+
+    fn tx(id: &str, status: TransactionStatus) -> CryptoWalletTransaction {
+        CryptoWalletTransaction {
+            amount_eur: Default::default(),
+            amount: Default::default(),
+            confirmations: 0,
+            cryptocoin_id: "1".to_string(),
+            current_fiat_amount: Default::default(),
+            current_fiat_id: "1".to_string(),
+            datetime: "2023-01-01T00:00:00+00:00".parse().unwrap(),
+            fee: Default::default(),
+            id: id.to_string(),
+            in_or_out: InOrOut::Incoming,
+            recipient: String::new(),
+            status,
+            transaction_type: TransactionType::Deposit,
+            wallet_id: "wallet".to_string(),
+        }
+    }
+}
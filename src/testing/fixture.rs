@@ -0,0 +1,356 @@
+//! # Fixture
+//!
+//! Builders of realistic trades and transactions to feed the mock server with
+
+use chrono::{DateTime, Duration, FixedOffset};
+use rust_decimal::Decimal;
+
+use crate::model::crypto_wallet::CryptoWalletTransaction;
+use crate::model::fiat_wallet::FiatWalletTransaction;
+use crate::model::{InOrOut, Trade, TradeStatus, TradeType, TransactionStatus, TransactionType};
+
+/// Date of the most recent record generated by the fixture functions
+const LATEST: &str = "2023-06-30T12:00:00+02:00";
+
+/// Transaction types cycled by the fixture functions
+const TRANSACTION_TYPES: [TransactionType; 5] = [
+    TransactionType::Buy,
+    TransactionType::Deposit,
+    TransactionType::Sell,
+    TransactionType::Withdrawal,
+    TransactionType::Transfer,
+];
+
+/// Parse an RFC 3339 datetime, e.g. `2023-01-01T12:00:00+00:00`
+pub fn datetime(s: &str) -> DateTime<FixedOffset> {
+    DateTime::parse_from_rfc3339(s).expect("invalid fixture datetime")
+}
+
+/// Get `n` finished trades, from the most recent, one hour apart.
+/// Buys and sells of BTC alternate, starting with a buy
+pub fn trades(n: usize) -> Vec<Trade> {
+    (0..n)
+        .map(|i| {
+            TradeBuilder::new(format!("trade-{i}"))
+                .r#type(if i % 2 == 0 {
+                    TradeType::Buy
+                } else {
+                    TradeType::Sell
+                })
+                .datetime(latest() - Duration::hours(i as i64))
+                .build()
+        })
+        .collect()
+}
+
+/// Get `n` crypto wallet transactions, from the most recent, one hour apart.
+/// The transaction types are cycled and every fourth transaction is canceled
+pub fn crypto_wallet_transactions(n: usize) -> Vec<CryptoWalletTransaction> {
+    (0..n)
+        .map(|i| {
+            CryptoWalletTransactionBuilder::new(format!("crypto-tx-{i}"))
+                .transaction_type(TRANSACTION_TYPES[i % TRANSACTION_TYPES.len()].clone())
+                .status(status(i))
+                .datetime(latest() - Duration::hours(i as i64))
+                .build()
+        })
+        .collect()
+}
+
+/// Get `n` fiat wallet transactions, from the most recent, one hour apart.
+/// The transaction types are cycled and every fourth transaction is canceled
+pub fn fiat_wallet_transactions(n: usize) -> Vec<FiatWalletTransaction> {
+    (0..n)
+        .map(|i| {
+            FiatWalletTransactionBuilder::new(format!("fiat-tx-{i}"))
+                .transaction_type(TRANSACTION_TYPES[i % TRANSACTION_TYPES.len()].clone())
+                .status(status(i))
+                .datetime(latest() - Duration::hours(i as i64))
+                .build()
+        })
+        .collect()
+}
+
+/// Trade builder.
+/// By default, the trade is a finished buy of 0.01 BTC for 200 EUR
+#[derive(Debug, Clone)]
+pub struct TradeBuilder {
+    trade: Trade,
+}
+
+impl TradeBuilder {
+    pub fn new(id: impl ToString) -> Self {
+        Self {
+            trade: Trade {
+                amount_asset: Decimal::new(1, 2),
+                amount_fiat: Decimal::new(200, 0),
+                datetime: latest(),
+                fiat_to_eur_rate: Decimal::ONE,
+                fiat_wallet_id: Some("fiat-wallet-eur".to_string()),
+                id_asset: "1".to_string(),
+                id_fiat: "1".to_string(),
+                id_wallet: "wallet-btc".to_string(),
+                id: id.to_string(),
+                price: Decimal::new(20000, 0),
+                related_swap_trade: None,
+                status: TradeStatus::Finished,
+                symbol: "BTC".to_string(),
+                r#type: TradeType::Buy,
+            },
+        }
+    }
+
+    pub fn r#type(mut self, r#type: TradeType) -> Self {
+        self.trade.r#type = r#type;
+
+        self
+    }
+
+    pub fn status(mut self, status: TradeStatus) -> Self {
+        self.trade.status = status;
+
+        self
+    }
+
+    pub fn datetime(mut self, datetime: impl Into<DateTime<FixedOffset>>) -> Self {
+        self.trade.datetime = datetime.into();
+
+        self
+    }
+
+    /// Set the traded asset and the id of its wallet
+    pub fn asset(mut self, id: impl ToString, symbol: impl ToString) -> Self {
+        self.trade.id_asset = id.to_string();
+        self.trade.symbol = symbol.to_string();
+        self.trade.id_wallet = format!("wallet-{}", self.trade.symbol.to_lowercase());
+
+        self
+    }
+
+    /// Set the amounts of the asset and the fiat traded; the price is computed from them
+    pub fn amounts(mut self, asset: Decimal, fiat: Decimal) -> Self {
+        self.trade.amount_asset = asset;
+        self.trade.amount_fiat = fiat;
+        if !asset.is_zero() {
+            self.trade.price = fiat / asset;
+        }
+
+        self
+    }
+
+    /// Set the fiat currency and its rate to EUR
+    pub fn fiat(mut self, id: impl ToString, to_eur_rate: Decimal) -> Self {
+        self.trade.id_fiat = id.to_string();
+        self.trade.fiat_to_eur_rate = to_eur_rate;
+
+        self
+    }
+
+    /// Make the trade a leg of a swap with `related`
+    pub fn swap(mut self, related: Trade) -> Self {
+        self.trade.related_swap_trade = Some(Box::new(related));
+
+        self
+    }
+
+    pub fn build(self) -> Trade {
+        self.trade
+    }
+}
+
+/// Crypto wallet transaction builder.
+/// By default, the transaction is a finished deposit of 0.01 BTC worth 200 EUR
+#[derive(Debug, Clone)]
+pub struct CryptoWalletTransactionBuilder {
+    transaction: CryptoWalletTransaction,
+}
+
+impl CryptoWalletTransactionBuilder {
+    pub fn new(id: impl ToString) -> Self {
+        Self {
+            transaction: CryptoWalletTransaction {
+                amount_eur: Decimal::new(200, 0),
+                amount: Decimal::new(1, 2),
+                confirmations: 6,
+                cryptocoin_id: "1".to_string(),
+                current_fiat_amount: Decimal::new(200, 0),
+                current_fiat_id: "1".to_string(),
+                datetime: latest(),
+                fee: Decimal::ZERO,
+                id: id.to_string(),
+                in_or_out: InOrOut::Incoming,
+                recipient: "bc1qmockrecipient".to_string(),
+                status: TransactionStatus::Finished,
+                transaction_type: TransactionType::Deposit,
+                wallet_id: "wallet-btc".to_string(),
+            },
+        }
+    }
+
+    /// Set the transaction type; the direction follows the type, unless it's unknown
+    pub fn transaction_type(mut self, transaction_type: TransactionType) -> Self {
+        if let Some(in_or_out) = in_or_out(&transaction_type) {
+            self.transaction.in_or_out = in_or_out;
+        }
+        self.transaction.transaction_type = transaction_type;
+
+        self
+    }
+
+    pub fn in_or_out(mut self, in_or_out: InOrOut) -> Self {
+        self.transaction.in_or_out = in_or_out;
+
+        self
+    }
+
+    pub fn status(mut self, status: TransactionStatus) -> Self {
+        self.transaction.status = status;
+
+        self
+    }
+
+    pub fn datetime(mut self, datetime: impl Into<DateTime<FixedOffset>>) -> Self {
+        self.transaction.datetime = datetime.into();
+
+        self
+    }
+
+    /// Set the cryptocoin id and the id of its wallet
+    pub fn cryptocoin(mut self, id: impl ToString, wallet_id: impl ToString) -> Self {
+        self.transaction.cryptocoin_id = id.to_string();
+        self.transaction.wallet_id = wallet_id.to_string();
+
+        self
+    }
+
+    /// Set the amount and its value in EUR
+    pub fn amount(mut self, amount: Decimal, amount_eur: Decimal) -> Self {
+        self.transaction.amount = amount;
+        self.transaction.amount_eur = amount_eur;
+        self.transaction.current_fiat_amount = amount_eur;
+
+        self
+    }
+
+    pub fn fee(mut self, fee: Decimal) -> Self {
+        self.transaction.fee = fee;
+
+        self
+    }
+
+    pub fn build(self) -> CryptoWalletTransaction {
+        self.transaction
+    }
+}
+
+/// Fiat wallet transaction builder.
+/// By default, the transaction is a finished deposit of 1000 EUR
+#[derive(Debug, Clone)]
+pub struct FiatWalletTransactionBuilder {
+    transaction: FiatWalletTransaction,
+}
+
+impl FiatWalletTransactionBuilder {
+    pub fn new(id: impl ToString) -> Self {
+        Self {
+            transaction: FiatWalletTransaction {
+                amount: Decimal::new(1000, 0),
+                datetime: latest(),
+                fee: Decimal::ZERO,
+                fiat_id: "1".to_string(),
+                id: id.to_string(),
+                in_or_out: InOrOut::Incoming,
+                status: TransactionStatus::Finished,
+                to_eur_rate: Decimal::ONE,
+                transaction_type: TransactionType::Deposit,
+                user_id: "mock-user".to_string(),
+                wallet_id: "fiat-wallet-eur".to_string(),
+            },
+        }
+    }
+
+    /// Set the transaction type; the direction follows the type, unless it's unknown
+    pub fn transaction_type(mut self, transaction_type: TransactionType) -> Self {
+        if let Some(in_or_out) = in_or_out(&transaction_type) {
+            self.transaction.in_or_out = in_or_out;
+        }
+        self.transaction.transaction_type = transaction_type;
+
+        self
+    }
+
+    pub fn in_or_out(mut self, in_or_out: InOrOut) -> Self {
+        self.transaction.in_or_out = in_or_out;
+
+        self
+    }
+
+    pub fn status(mut self, status: TransactionStatus) -> Self {
+        self.transaction.status = status;
+
+        self
+    }
+
+    pub fn datetime(mut self, datetime: impl Into<DateTime<FixedOffset>>) -> Self {
+        self.transaction.datetime = datetime.into();
+
+        self
+    }
+
+    /// Set the fiat currency, its rate to EUR and the id of its wallet
+    pub fn fiat(
+        mut self,
+        id: impl ToString,
+        to_eur_rate: Decimal,
+        wallet_id: impl ToString,
+    ) -> Self {
+        self.transaction.fiat_id = id.to_string();
+        self.transaction.to_eur_rate = to_eur_rate;
+        self.transaction.wallet_id = wallet_id.to_string();
+
+        self
+    }
+
+    pub fn amount(mut self, amount: Decimal) -> Self {
+        self.transaction.amount = amount;
+
+        self
+    }
+
+    pub fn fee(mut self, fee: Decimal) -> Self {
+        self.transaction.fee = fee;
+
+        self
+    }
+
+    pub fn build(self) -> FiatWalletTransaction {
+        self.transaction
+    }
+}
+
+fn latest() -> DateTime<FixedOffset> {
+    DateTime::parse_from_rfc3339(LATEST).unwrap()
+}
+
+fn status(i: usize) -> TransactionStatus {
+    if i % 4 == 3 {
+        TransactionStatus::Canceled
+    } else {
+        TransactionStatus::Finished
+    }
+}
+
+/// Direction of the transactions of type `transaction_type` from the user's point of view
+fn in_or_out(transaction_type: &TransactionType) -> Option<InOrOut> {
+    let in_or_out = match transaction_type {
+        TransactionType::Buy
+        | TransactionType::Deposit
+        | TransactionType::Ico
+        | TransactionType::Refund
+        | TransactionType::Transfer => InOrOut::Incoming,
+        TransactionType::Sell | TransactionType::Withdrawal => InOrOut::Outgoing,
+        TransactionType::Unknown(_) => return None,
+    };
+
+    Some(in_or_out)
+}
@@ -0,0 +1,259 @@
+//! # Json
+//!
+//! Encoding of the models into the JSON:API payloads of Bitpanda
+
+use chrono::{DateTime, FixedOffset};
+use serde_json::{json, Value};
+
+use crate::model::crypto_wallet::CryptoWalletTransaction;
+use crate::model::fiat_wallet::FiatWalletTransaction;
+use crate::model::{Asset, AssetClass, AssetWallet, CryptoWallet, FiatWallet, Ticker, Trade};
+use crate::model::{OpenHighLowCloseChart, TradeType};
+
+pub fn asset_wallets(wallets: &[AssetWallet]) -> Value {
+    let collection = |class: AssetClass| {
+        let wallets: Vec<Value> = wallets
+            .iter()
+            .filter(|wallet| wallet.class == class)
+            .map(|wallet| {
+                json!({
+                    "type": "wallet",
+                    "id": wallet.id,
+                    "attributes": {
+                        "cryptocoin_id": wallet.asset_id,
+                        "cryptocoin_symbol": wallet.asset_symbol,
+                        "balance": wallet.balance.to_string(),
+                        "is_default": wallet.is_default,
+                        "name": wallet.name,
+                        "deleted": wallet.deleted,
+                    }
+                })
+            })
+            .collect();
+        json!({
+            "type": "collection",
+            "attributes": { "wallets": wallets }
+        })
+    };
+
+    json!({
+        "data": {
+            "type": "data",
+            "attributes": {
+                "cryptocoin": collection(AssetClass::Cryptocurrency),
+                "commodity": { "metal": collection(AssetClass::Metal) },
+                "index": { "index": collection(AssetClass::Cryptoindex) },
+                "security": {
+                    "etc": collection(AssetClass::Commodity),
+                    "etf": collection(AssetClass::Etf),
+                    "stock": collection(AssetClass::Stock),
+                },
+            }
+        }
+    })
+}
+
+pub fn crypto_wallets(wallets: &[CryptoWallet]) -> Value {
+    let data: Vec<Value> = wallets
+        .iter()
+        .map(|wallet| {
+            json!({
+                "type": "wallet",
+                "id": wallet.id,
+                "attributes": {
+                    "balance": wallet.balance.to_string(),
+                    "cryptocoin_id": wallet.cryptocoin_id,
+                    "cryptocoin_symbol": wallet.symbol,
+                    "deleted": wallet.deleted,
+                    "is_default": wallet.is_default,
+                    "name": wallet.name,
+                    "pending_transactions_count": wallet.pending_transactions_count,
+                }
+            })
+        })
+        .collect();
+
+    json!({ "data": data })
+}
+
+pub fn fiat_wallets(wallets: &[FiatWallet]) -> Value {
+    let data: Vec<Value> = wallets
+        .iter()
+        .map(|wallet| {
+            json!({
+                "type": "fiat_wallet",
+                "id": wallet.id,
+                "attributes": {
+                    "fiat_id": wallet.fiat_id,
+                    "fiat_symbol": wallet.symbol,
+                    "balance": wallet.balance.to_string(),
+                    "name": wallet.name,
+                    "pending_transactions_count": wallet.pending_transactions_count,
+                }
+            })
+        })
+        .collect();
+
+    json!({ "data": data })
+}
+
+pub fn trade(trade: &Trade) -> Value {
+    json!({
+        "type": "trade",
+        "id": trade.id,
+        "attributes": {
+            "status": trade.status.to_string(),
+            "type": trade.r#type.to_string(),
+            "cryptocoin_id": trade.id_asset,
+            "cryptocoin_symbol": trade.symbol,
+            "fiat_id": trade.id_fiat,
+            "amount_fiat": trade.amount_fiat.to_string(),
+            "amount_cryptocoin": trade.amount_asset.to_string(),
+            "fiat_to_eur_rate": trade.fiat_to_eur_rate.to_string(),
+            "wallet_id": trade.id_wallet,
+            "fiat_wallet_id": trade.fiat_wallet_id,
+            "related_swap_trade": trade.related_swap_trade.as_deref().map(self::trade),
+            "is_swap": trade.related_swap_trade.is_some(),
+            "is_savings": false,
+            "time": time(trade.datetime),
+            "price": trade.price.to_string(),
+            "bfc_used": false,
+            "best_price": trade.price.to_string(),
+            "is_card": trade.r#type == TradeType::Buy && trade.fiat_wallet_id.is_none(),
+        }
+    })
+}
+
+pub fn crypto_wallet_transaction(tx: &CryptoWalletTransaction) -> Value {
+    json!({
+        "type": "wallet_transaction",
+        "id": tx.id,
+        "attributes": {
+            "amount": tx.amount.to_string(),
+            "recipient": tx.recipient,
+            "time": time(tx.datetime),
+            "confirmations": tx.confirmations,
+            "in_or_out": tx.in_or_out.to_string(),
+            "type": tx.transaction_type.to_string(),
+            "status": tx.status.to_string(),
+            "amount_eur": tx.amount_eur.to_string(),
+            "wallet_id": tx.wallet_id,
+            "cryptocoin_id": tx.cryptocoin_id,
+            "fee": tx.fee.to_string(),
+            "current_fiat_id": tx.current_fiat_id,
+            "current_fiat_amount": tx.current_fiat_amount.to_string(),
+            "is_bfc": false,
+            "is_metal_storage_fee": false,
+        }
+    })
+}
+
+pub fn fiat_wallet_transaction(tx: &FiatWalletTransaction) -> Value {
+    json!({
+        "type": "fiat_wallet_transaction",
+        "id": tx.id,
+        "attributes": {
+            "fiat_wallet_id": tx.wallet_id,
+            "user_id": tx.user_id,
+            "fiat_id": tx.fiat_id,
+            "amount": tx.amount.to_string(),
+            "fee": tx.fee.to_string(),
+            "to_eur_rate": tx.to_eur_rate.to_string(),
+            "time": time(tx.datetime),
+            "in_or_out": tx.in_or_out.to_string(),
+            "type": tx.transaction_type.to_string(),
+            "status": tx.status.to_string(),
+        }
+    })
+}
+
+pub fn asset(asset: &Asset) -> Value {
+    json!({
+        "type": "asset",
+        "id": asset.id,
+        "attributes": {
+            "pid": asset.pid,
+            "symbol": asset.symbol,
+            "name": asset.name,
+        }
+    })
+}
+
+pub fn ohlc(chart: &OpenHighLowCloseChart) -> Value {
+    let data: Vec<Value> = chart
+        .chart
+        .iter()
+        .map(|candle| {
+            json!({
+                "type": "candle",
+                "attributes": {
+                    "open": candle.open.to_string(),
+                    "high": candle.high.to_string(),
+                    "low": candle.low.to_string(),
+                    "close": candle.close.to_string(),
+                    "time": time(candle.time),
+                }
+            })
+        })
+        .collect();
+
+    json!({ "data": data })
+}
+
+pub fn ticker(ticker: &Ticker) -> Value {
+    serde_json::to_value(&ticker.prices).unwrap_or_default()
+}
+
+/// Page of a collection, with the JSON:API links to the other pages
+pub fn page(data: Vec<Value>, links: Links, total_count: usize) -> Value {
+    json!({
+        "data": data,
+        "meta": {
+            "total_count": total_count,
+            "page": links.page,
+            "page_size": links.page_size,
+        },
+        "links": {
+            "next": links.next(),
+            "self": links.url(links.page),
+        }
+    })
+}
+
+pub fn single(data: Value) -> Value {
+    json!({ "data": data })
+}
+
+pub fn error(status: u16, title: &str, detail: &str) -> Value {
+    json!({
+        "errors": [
+            { "status": status, "title": title, "detail": detail }
+        ]
+    })
+}
+
+/// Links of a page
+pub struct Links {
+    pub page: usize,
+    pub page_size: usize,
+    pub has_next: bool,
+    /// Query arguments which are not related to pagination
+    pub query: String,
+}
+
+impl Links {
+    fn next(&self) -> Option<String> {
+        self.has_next.then(|| self.url(self.page + 1))
+    }
+
+    fn url(&self, page: usize) -> String {
+        format!("?page={page}&page_size={}{}", self.page_size, self.query)
+    }
+}
+
+fn time(datetime: DateTime<FixedOffset>) -> Value {
+    json!({
+        "date_iso8601": datetime.to_rfc3339(),
+        "unix": datetime.timestamp().to_string(),
+    })
+}
@@ -0,0 +1,28 @@
+//! # Testing
+//!
+//! Offline mock of the Bitpanda API, available with the `testing` feature.
+//!
+//! [`MockTransport`] serves the fixtures it's fed with in-process, as the Bitpanda API would, while
+//! [`MockServer`] serves them over a local HTTP server.
+//! The [`fixture`] module provides builders for realistic trades and transactions.
+//!
+//! ```rust
+//! use bitpanda_api::testing::{fixture, MockServer};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let server = MockServer::start().await;
+//!     server.set_trades(fixture::trades(30));
+//!
+//!     let trades = server.client().get_trades().await.unwrap();
+//!     assert_eq!(trades.len(), 30);
+//! }
+//! ```
+
+pub mod fixture;
+mod json;
+mod server;
+mod transport;
+
+pub use server::MockServer;
+pub use transport::{MockTransport, MOCK_API_KEY};
@@ -0,0 +1,123 @@
+//! # Server
+//!
+//! Mock Bitpanda HTTP server
+
+use std::convert::Infallible;
+use std::net::{SocketAddr, TcpListener};
+use std::ops::Deref;
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use tokio::sync::oneshot;
+
+use super::transport::MockTransport;
+use crate::{Client, ClientBuilder};
+
+/// Local HTTP server which serves a [`MockTransport`] over HTTP.
+///
+/// The fixtures are set through the methods of the [`MockTransport`] the server dereferences to.
+/// The server is stopped when dropped.
+pub struct MockServer {
+    addr: SocketAddr,
+    transport: MockTransport,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockServer {
+    /// Start the server on a random local port.
+    /// Must be called within a tokio runtime
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind mock server");
+        listener
+            .set_nonblocking(true)
+            .expect("failed to set mock server socket non-blocking");
+        let addr = listener.local_addr().expect("mock server has no address");
+        let transport = MockTransport::new();
+
+        let service_transport = transport.clone();
+        let make_service = make_service_fn(move |_| {
+            let transport = service_transport.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let transport = transport.clone();
+                    async move { Ok::<_, Infallible>(serve(&transport, request)) }
+                }))
+            }
+        });
+        let (shutdown, on_shutdown) = oneshot::channel::<()>();
+        let server = Server::from_tcp(listener)
+            .expect("failed to start mock server")
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                let _ = on_shutdown.await;
+            });
+        tokio::spawn(server);
+        debug!("mock server listening on {addr}");
+
+        Self {
+            addr,
+            transport,
+            shutdown: Some(shutdown),
+        }
+    }
+
+    /// Base URL of the server
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Get a client builder pointed to the server and with the mock api key
+    pub fn client_builder(&self) -> ClientBuilder {
+        Client::builder()
+            .x_apikey(super::MOCK_API_KEY)
+            .api_url(format!("{}/v1", self.url()))
+            .public_url(format!("{}/v3", self.url()))
+    }
+
+    /// Get a client pointed to the server and with the mock api key
+    pub fn client(&self) -> Client {
+        self.client_builder().build()
+    }
+}
+
+impl Deref for MockServer {
+    type Target = MockTransport;
+
+    fn deref(&self) -> &Self::Target {
+        &self.transport
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+fn serve(transport: &MockTransport, request: Request<Body>) -> Response<Body> {
+    if request.method() != Method::GET {
+        return Response::builder()
+            .status(StatusCode::METHOD_NOT_ALLOWED)
+            .body(Body::empty())
+            .expect("invalid mock response");
+    }
+    let api_key = request
+        .headers()
+        .get("X-API-KEY")
+        .and_then(|value| value.to_str().ok());
+    let reply = transport.respond(
+        request.uri().path(),
+        request.uri().query().unwrap_or_default(),
+        api_key,
+    );
+
+    let mut response = Response::builder().status(reply.status);
+    for (name, value) in reply.headers {
+        response = response.header(name, value);
+    }
+    response
+        .body(Body::from(reply.body))
+        .expect("invalid mock response")
+}
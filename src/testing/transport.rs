@@ -0,0 +1,375 @@
+//! # Transport
+//!
+//! In-process mock of the Bitpanda API
+
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use reqwest::header::{CONTENT_TYPE, RETRY_AFTER};
+use reqwest::Url;
+use serde_json::Value;
+
+use super::json;
+use crate::api::{ApiError, ApiResult, HttpRequest, HttpResponse, Transport};
+use crate::model::crypto_wallet::CryptoWalletTransaction;
+use crate::model::fiat_wallet::FiatWalletTransaction;
+use crate::model::{
+    Asset, AssetWallet, CryptoWallet, FiatWallet, OpenHighLowCloseChart, Ticker, Trade,
+};
+use crate::{Client, ClientBuilder};
+
+/// Api key accepted by the mock
+pub const MOCK_API_KEY: &str = "bitpanda-mock-api-key";
+
+/// Base URL of the mock, used by the clients built by [`MockTransport::client_builder`]
+const MOCK_URL: &str = "http://bitpanda.mock";
+
+const DEFAULT_PAGE_SIZE: usize = 25;
+
+/// Transport which serves the Bitpanda API out of the provided fixtures, without any network.
+///
+/// The private v1 API is served under `/v1` and requires [`MOCK_API_KEY`], while the public v3 API is
+/// served under `/v3`. Collections are paginated as Bitpanda does, with the `next` link set while there are
+/// pages left, and filtered by `type` and `status`. Clones share the same fixtures.
+#[derive(Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    asset_wallets: Vec<AssetWallet>,
+    crypto_wallets: Vec<CryptoWallet>,
+    fiat_wallets: Vec<FiatWallet>,
+    trades: Vec<Trade>,
+    crypto_wallet_transactions: Vec<CryptoWalletTransaction>,
+    fiat_wallet_transactions: Vec<FiatWalletTransaction>,
+    assets: Vec<Asset>,
+    /// Charts by asset pid and currency
+    ohlc: Vec<(String, String, OpenHighLowCloseChart)>,
+    ticker: Ticker,
+    failures: VecDeque<Reply>,
+    requests: Vec<String>,
+}
+
+/// A response of the mock
+#[derive(Debug, Clone)]
+struct Reply {
+    status: u16,
+    retry_after: Option<Duration>,
+    body: Value,
+}
+
+impl Reply {
+    fn ok(body: Value) -> Self {
+        Self {
+            status: 200,
+            retry_after: None,
+            body,
+        }
+    }
+
+    fn error(status: u16, title: &str, detail: &str) -> Self {
+        Self {
+            status,
+            retry_after: None,
+            body: json::error(status, title, detail),
+        }
+    }
+
+    fn not_found(detail: &str) -> Self {
+        Self::error(404, "Not found", detail)
+    }
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a client builder sending its requests through the mock, with the mock api key
+    pub fn client_builder(&self) -> ClientBuilder {
+        Client::builder()
+            .x_apikey(MOCK_API_KEY)
+            .api_url(format!("{MOCK_URL}/v1"))
+            .public_url(format!("{MOCK_URL}/v3"))
+            .transport(self.clone())
+    }
+
+    /// Get a client sending its requests through the mock, with the mock api key
+    pub fn client(&self) -> Client {
+        self.client_builder().build()
+    }
+
+    /// Set the asset wallets returned by the mock
+    pub fn set_asset_wallets(&self, wallets: Vec<AssetWallet>) {
+        self.state().asset_wallets = wallets;
+    }
+
+    /// Set the crypto wallets returned by the mock
+    pub fn set_crypto_wallets(&self, wallets: Vec<CryptoWallet>) {
+        self.state().crypto_wallets = wallets;
+    }
+
+    /// Set the fiat wallets returned by the mock
+    pub fn set_fiat_wallets(&self, wallets: Vec<FiatWallet>) {
+        self.state().fiat_wallets = wallets;
+    }
+
+    /// Set the trades returned by the mock. They are served from the most recent
+    pub fn set_trades(&self, trades: Vec<Trade>) {
+        self.state().trades = trades;
+    }
+
+    /// Set the crypto wallet transactions returned by the mock. They are served from the most recent
+    pub fn set_crypto_wallet_transactions(&self, transactions: Vec<CryptoWalletTransaction>) {
+        self.state().crypto_wallet_transactions = transactions;
+    }
+
+    /// Set the fiat wallet transactions returned by the mock. They are served from the most recent
+    pub fn set_fiat_wallet_transactions(&self, transactions: Vec<FiatWalletTransaction>) {
+        self.state().fiat_wallet_transactions = transactions;
+    }
+
+    /// Set the assets returned by the mock; they are filtered by their type
+    pub fn set_assets(&self, assets: Vec<Asset>) {
+        self.state().assets = assets;
+    }
+
+    /// Set the OHLC chart of the asset with `pid` in `currency` for the chart period
+    pub fn set_ohlc(&self, pid: &str, currency: &str, chart: OpenHighLowCloseChart) {
+        let mut state = self.state();
+        state
+            .ohlc
+            .retain(|(p, c, other)| !(p == pid && c == currency && other.period == chart.period));
+        state
+            .ohlc
+            .push((pid.to_string(), currency.to_string(), chart));
+    }
+
+    /// Set the ticker returned by the mock
+    pub fn set_ticker(&self, ticker: Ticker) {
+        self.state().ticker = ticker;
+    }
+
+    /// Make the next request fail with `status`, optionally with the `Retry-After` header.
+    /// Failures are queued, so calling this `n` times makes the next `n` requests fail
+    pub fn fail_next(&self, status: u16, retry_after: Option<Duration>) {
+        self.state().failures.push_back(Reply {
+            retry_after,
+            ..Reply::error(status, "Mock failure", "failure injected by the mock")
+        });
+    }
+
+    /// Get the path and query of the requests received so far
+    pub fn requests(&self) -> Vec<String> {
+        self.state().requests.clone()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    /// Respond to the GET request to `path` with `query`
+    pub(super) fn respond(&self, path: &str, query: &str, api_key: Option<&str>) -> HttpResponse {
+        let reply = {
+            let mut state = self.state();
+            state.requests.push(match query.is_empty() {
+                true => path.to_string(),
+                false => format!("{path}?{query}"),
+            });
+            match state.failures.pop_front() {
+                Some(failure) => failure,
+                None => route(&state, path, query, api_key == Some(MOCK_API_KEY)),
+            }
+        };
+        trace!("mock: GET {path}?{query} -> {}", reply.status);
+
+        let mut headers = vec![(CONTENT_TYPE.to_string(), "application/json".to_string())];
+        if let Some(retry_after) = reply.retry_after {
+            headers.push((RETRY_AFTER.to_string(), retry_after.as_secs().to_string()));
+        }
+        HttpResponse {
+            status: reply.status,
+            headers,
+            body: reply.body.to_string(),
+        }
+    }
+}
+
+impl Transport for MockTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, ApiResult<HttpResponse>> {
+        Box::pin(async move {
+            let url = Url::parse(&request.url).map_err(|err| ApiError::Transport {
+                message: format!("invalid url {}: {err}", request.url),
+                transient: false,
+            })?;
+
+            Ok(self.respond(
+                url.path(),
+                url.query().unwrap_or_default(),
+                request.header("X-API-KEY"),
+            ))
+        })
+    }
+}
+
+fn route(state: &State, path: &str, query: &str, authorized: bool) -> Reply {
+    let args = Query::parse(query);
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match segments.as_slice() {
+        ["v1", "ticker"] => Reply::ok(json::ticker(&state.ticker)),
+        ["v1", ..] if !authorized => Reply::error(401, "Unauthorized", "invalid api key"),
+        ["v1", "asset-wallets"] => Reply::ok(json::asset_wallets(&state.asset_wallets)),
+        ["v1", "wallets"] => Reply::ok(json::crypto_wallets(&state.crypto_wallets)),
+        ["v1", "fiatwallets"] => Reply::ok(json::fiat_wallets(&state.fiat_wallets)),
+        ["v1", "trades"] => {
+            let mut trades: Vec<&Trade> = state
+                .trades
+                .iter()
+                .filter(|trade| args.matches("type", &trade.r#type.to_string()))
+                .collect();
+            trades.sort_by_key(|item| Reverse(item.datetime));
+            paginate(&args, &["type"], trades, json::trade)
+        }
+        ["v1", "trades", id] => find(&state.trades, |trade| trade.id == *id, json::trade),
+        ["v1", "wallets", "transactions"] => {
+            let mut transactions: Vec<&CryptoWalletTransaction> = state
+                .crypto_wallet_transactions
+                .iter()
+                .filter(|tx| {
+                    args.matches("type", &tx.transaction_type.to_string())
+                        && args.matches("status", &tx.status.to_string())
+                })
+                .collect();
+            transactions.sort_by_key(|item| Reverse(item.datetime));
+            paginate(
+                &args,
+                &["type", "status"],
+                transactions,
+                json::crypto_wallet_transaction,
+            )
+        }
+        ["v1", "wallets", "transactions", id] => find(
+            &state.crypto_wallet_transactions,
+            |tx| tx.id == *id,
+            json::crypto_wallet_transaction,
+        ),
+        ["v1", "fiatwallets", "transactions"] => {
+            let mut transactions: Vec<&FiatWalletTransaction> = state
+                .fiat_wallet_transactions
+                .iter()
+                .filter(|tx| {
+                    args.matches("type", &tx.transaction_type.to_string())
+                        && args.matches("status", &tx.status.to_string())
+                })
+                .collect();
+            transactions.sort_by_key(|item| Reverse(item.datetime));
+            paginate(
+                &args,
+                &["type", "status"],
+                transactions,
+                json::fiat_wallet_transaction,
+            )
+        }
+        ["v1", "fiatwallets", "transactions", id] => find(
+            &state.fiat_wallet_transactions,
+            |tx| tx.id == *id,
+            json::fiat_wallet_transaction,
+        ),
+        ["v3", "assets"] => {
+            let assets: Vec<&Asset> = state
+                .assets
+                .iter()
+                .filter(|asset| args.matches("type[]", &asset.r#type.to_string()))
+                .collect();
+            paginate(&args, &["type[]"], assets, json::asset)
+        }
+        ["v3", "ohlc", pid, currency, period] => state
+            .ohlc
+            .iter()
+            .find(|(p, c, chart)| p == pid && c == currency && chart.period.to_string() == *period)
+            .map(|(_, _, chart)| Reply::ok(json::ohlc(chart)))
+            .unwrap_or_else(|| Reply::not_found("chart not found")),
+        _ => Reply::not_found("no such endpoint"),
+    }
+}
+
+/// Serve a page of `items`; pages start from 1, and page 0 is the same as page 1
+fn paginate<T>(args: &Query, filters: &[&str], items: Vec<&T>, encode: fn(&T) -> Value) -> Reply {
+    let page = args
+        .get("page")
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(1_usize)
+        .max(1);
+    let page_size = args
+        .get("page_size")
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .max(1);
+    let total_count = items.len();
+    let data = items
+        .into_iter()
+        .skip((page - 1) * page_size)
+        .take(page_size)
+        .map(encode)
+        .collect();
+    let links = json::Links {
+        page,
+        page_size,
+        has_next: page * page_size < total_count,
+        query: filters
+            .iter()
+            .filter_map(|key| args.get(key).map(|value| format!("&{key}={value}")))
+            .collect(),
+    };
+
+    Reply::ok(json::page(data, links, total_count))
+}
+
+fn find<T>(items: &[T], predicate: impl Fn(&T) -> bool, encode: fn(&T) -> Value) -> Reply {
+    items
+        .iter()
+        .find(|item| predicate(item))
+        .map(|item| Reply::ok(json::single(encode(item))))
+        .unwrap_or_else(|| Reply::not_found("resource not found"))
+}
+
+/// Query arguments of a request
+struct Query(Vec<(String, String)>);
+
+impl Query {
+    fn parse(query: &str) -> Self {
+        Self(
+            query
+                .split('&')
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| match arg.split_once('=') {
+                    Some((key, value)) => (decode(key), decode(value)),
+                    None => (decode(arg), String::new()),
+                })
+                .collect(),
+        )
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Whether the argument `key` is either unset or set to `value`
+    fn matches(&self, key: &str, value: &str) -> bool {
+        self.get(key).map_or(true, |expected| expected == value)
+    }
+}
+
+/// Decode the percent-encoded characters used by the client
+fn decode(s: &str) -> String {
+    s.replace("%5B", "[").replace("%5D", "]")
+}